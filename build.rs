@@ -0,0 +1,30 @@
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+
+    #[cfg(feature = "ffi")]
+    ffi::generate();
+}
+
+#[cfg(feature = "ffi")]
+mod ffi {
+    pub fn generate() {
+        println!("cargo:rerun-if-changed=src/ffi.rs");
+        println!("cargo:rerun-if-changed=tests/c/ffi_smoke.c");
+
+        // Generates the C header for the `extern "C"` functions in `src/ffi.rs`
+        let crate_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+        cbindgen::Builder::new()
+            .with_crate(&crate_dir)
+            .with_language(cbindgen::Language::C)
+            .with_include_guard("FIXED_POINT_ARITHMETIC_H")
+            .generate()
+            .expect("Unable to generate C bindings")
+            .write_to_file("include/fixed_point_arithmetic.h");
+
+        // Compiles the C snippet exercised by `ffi::tests::test_c_snippet`
+        cc::Build::new()
+            .file("tests/c/ffi_smoke.c")
+            .include("include")
+            .compile("ffi_smoke");
+    }
+}
@@ -0,0 +1,65 @@
+use fixed_point_arithmetic::{
+    fixed::Fixed32,
+    fixed_tfhe::TfheFixed32,
+};
+use tfhe::{
+    generate_keys,
+    set_server_key,
+    ConfigBuilder,
+};
+
+const EXP: u32 = 16;
+
+// The lender's public model: weights for income and debt (both in thousands)
+// and a bias
+const INCOME_WEIGHT: f32 = 0.04;
+const DEBT_WEIGHT: f32 = -0.08;
+const BIAS: f32 = -1.;
+
+// A common degree-3 approximation of the logistic sigmoid, within about
+// 0.05 on [-5, 5], lowest power first. Polynomials only need additions and
+// multiplications, which is all the encrypted type supports
+const SIGMOID_COEFFICIENTS: [f32; 4] = [0.5, 0.197, 0., -0.004];
+
+fn server_score(income: &TfheFixed32, debt: &TfheFixed32) -> TfheFixed32 {
+    // Runs on the server, which only ever sees ciphertexts and the public
+    // model
+    let fixed = |x: f32| Fixed32::from(x, EXP as i32);
+    let z = income.clone().mul_plaintext(fixed(INCOME_WEIGHT))
+        + debt.clone().mul_plaintext(fixed(DEBT_WEIGHT));
+    let z = z.add_plaintext(fixed(BIAS));
+
+    let z_cubed = z.clone() * z.clone() * z.clone();
+    z.mul_plaintext(fixed(SIGMOID_COEFFICIENTS[1]))
+        .add_plaintext(fixed(SIGMOID_COEFFICIENTS[0]))
+        + z_cubed.mul_plaintext(fixed(SIGMOID_COEFFICIENTS[3]))
+}
+
+fn plaintext_score(income: f32, debt: f32) -> f32 {
+    let z = INCOME_WEIGHT * income + DEBT_WEIGHT * debt + BIAS;
+    1. / (1. + (-z).exp())
+}
+
+// Scores a loan applicant without the lender learning their finances: the
+// applicant encrypts income and debt, the lender evaluates a logistic model on
+// the ciphertexts, and only the applicant can decrypt the score. Run with
+// `cargo run --release --example credit_score`
+fn main() {
+    // Applicant: generates the keys and keeps the client key private
+    let config = ConfigBuilder::default().build();
+    let (client_key, server_key) = generate_keys(config);
+
+    let (income, debt) = (55., 12.5);
+    let encrypted_income = TfheFixed32::from(&client_key, income, EXP);
+    let encrypted_debt = TfheFixed32::from(&client_key, debt, EXP);
+
+    // Lender: receives the server key and the two ciphertexts
+    set_server_key(server_key);
+    let encrypted_score = server_score(&encrypted_income, &encrypted_debt);
+
+    // Applicant: decrypts the result
+    let score = encrypted_score.to_f32(&client_key);
+    println!("income: {}k, debt: {}k", income, debt);
+    println!("encrypted score: {:.4}", score);
+    println!("plaintext score: {:.4}", plaintext_score(income, debt));
+}
@@ -0,0 +1,21 @@
+# Build and install the extension first:
+#
+#     maturin develop --release
+#
+from fixed_point_arithmetic import Fixed32
+
+EXP = 24
+
+divisor = 3.15
+d = Fixed32.from_float(divisor, EXP)
+two = Fixed32.from_float(2.0, EXP)
+
+# Initial guess for 1 / d; every iteration roughly doubles the correct bits
+x = Fixed32.from_float(0.25, EXP)
+for i in range(5):
+    x = x * (two - d * x)
+    print(f"iteration {i}: {x.to_float():.8f}")
+
+print(f"fixed-point result: {x!r}")
+print(f"float result:       {1 / divisor:.8f}")
+print(f"Rust reciprocal:    {d.reciprocal().to_float():.8f}")
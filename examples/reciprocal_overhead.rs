@@ -0,0 +1,26 @@
+use fixed_point_arithmetic::{
+    fixed::Fixed32,
+    fixed_tfhe::TfheFixed32,
+    measure::compare_plaintext_fhe,
+};
+use tfhe::{
+    generate_keys,
+    set_server_key,
+    ConfigBuilder,
+};
+
+// Prints the cost of computing `reciprocal` on encrypted data compared to
+// plaintext. Run with `cargo run --release --example reciprocal_overhead`
+fn main() {
+    let config = ConfigBuilder::default().build();
+    let (client_key, server_key) = generate_keys(config);
+    set_server_key(server_key);
+
+    let plain = Fixed32::from(3.15, 24);
+    let encrypted = TfheFixed32::from(&client_key, 3.15, 24);
+    compare_plaintext_fhe(
+        || plain.reciprocal(),
+        || encrypted.reciprocal(),
+        "reciprocal of 3.15 (exp = 24)",
+    );
+}
@@ -0,0 +1,14 @@
+// Build the package for Node.js first:
+//
+//     wasm-pack build --target nodejs -- --features wasm
+//
+const { Fixed32 } = require("../../pkg/fixed_point_arithmetic.js");
+
+const EXP = 24;
+
+const x = Fixed32.from_f32(3.14, EXP);
+console.log(`fixed-point result: ${x.reciprocal().to_f32()}`);
+console.log(`float result:       ${1 / 3.14}`);
+
+const two = Fixed32.from_f32(2.0, EXP);
+console.log(`3.14 * 2 = ${x.mul(two).to_f32()}`);
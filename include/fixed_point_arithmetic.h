@@ -0,0 +1,26 @@
+#ifndef FIXED_POINT_ARITHMETIC_H
+#define FIXED_POINT_ARITHMETIC_H
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+typedef struct Fixed32Ffi {
+  int32_t value;
+  int32_t exp;
+} Fixed32Ffi;
+
+struct Fixed32Ffi fixed32_new(int32_t value, int32_t exp);
+
+struct Fixed32Ffi fixed32_from_f32(float value, int32_t exp);
+
+float fixed32_to_f32(struct Fixed32Ffi x);
+
+struct Fixed32Ffi fixed32_add(struct Fixed32Ffi a, struct Fixed32Ffi b);
+
+struct Fixed32Ffi fixed32_mul(struct Fixed32Ffi a, struct Fixed32Ffi b);
+
+struct Fixed32Ffi fixed32_reciprocal(struct Fixed32Ffi x);
+
+#endif /* FIXED_POINT_ARITHMETIC_H */
@@ -0,0 +1,310 @@
+use crate::{
+    fixed::Fixed32,
+    fixed_tfhe::TfheFixed32,
+};
+use std::f64::consts::PI;
+
+pub fn eval_poly(coefficients: &[Fixed32], x: Fixed32) -> Fixed32 {
+    // Evaluates `c[0] + c[1] * x + c[2] * x^2 + ...` using Horner's method
+    let mut iter = coefficients.iter().rev();
+    let mut result = match iter.next() {
+        Some(&leading) => leading,
+        None => return Fixed32::new(0, x.exp()),
+    };
+    for &coefficient in iter {
+        result = result * x + coefficient;
+    }
+
+    result
+}
+
+pub fn chebyshev_approximation(
+    f: impl Fn(f64) -> f64,
+    degree: usize,
+    a: f64,
+    b: f64,
+    exp: i32,
+) -> Vec<Fixed32> {
+    // Interpolates `f` at the Chebyshev nodes of [a, b] and returns the
+    // monomial coefficients in increasing order of power, ready for
+    // `eval_poly`
+    let n = degree + 1;
+    let half_width = (b - a) / 2.;
+    let center = (a + b) / 2.;
+    let samples: Vec<f64> = (0..n)
+        .map(|j| {
+            let node = (PI * (j as f64 + 0.5) / n as f64).cos();
+            f(center + half_width * node)
+        })
+        .collect();
+
+    // Chebyshev coefficients via the discrete cosine transform
+    let chebyshev: Vec<f64> = (0..n)
+        .map(|k| {
+            let sum: f64 = samples
+                .iter()
+                .enumerate()
+                .map(|(j, sample)| {
+                    sample * (PI * k as f64 * (j as f64 + 0.5) / n as f64).cos()
+                })
+                .sum();
+            let scale = if k == 0 { 1. } else { 2. };
+            scale * sum / n as f64
+        })
+        .collect();
+
+    // Expands the Chebyshev series into monomials of t in [-1, 1] using
+    // T(k + 1) = 2t * T(k) - T(k - 1)
+    let mut in_t = vec![0.; n];
+    let mut previous = vec![0.; n];
+    let mut current = vec![0.; n];
+    current[0] = 1.;
+    for (k, &c) in chebyshev.iter().enumerate() {
+        for (term, basis) in in_t.iter_mut().zip(&current) {
+            *term += c * basis;
+        }
+
+        let mut next = vec![0.; n];
+        for i in 0..n {
+            let shifted = if i > 0 { current[i - 1] } else { 0. };
+            let factor = if k == 0 { 1. } else { 2. };
+            next[i] = factor * shifted - previous[i];
+        }
+        previous = current;
+        current = next;
+    }
+
+    // Substitutes t = (x - center) / half_width using Horner's method on
+    // polynomials
+    let scale = 1. / half_width;
+    let offset = -center / half_width;
+    let mut in_x = vec![0.; n];
+    for &term in in_t.iter().rev() {
+        let mut next = vec![0.; n];
+        for i in 0..n {
+            next[i] += in_x[i] * offset;
+            if i + 1 < n {
+                next[i + 1] += in_x[i] * scale;
+            }
+        }
+        next[0] += term;
+        in_x = next;
+    }
+
+    in_x.iter()
+        .map(|&c| {
+            let scaled = (c * (1i64 << exp) as f64).round();
+            Fixed32::new(scaled as i32, exp)
+        })
+        .collect()
+}
+
+pub fn piecewise_linear(x: Fixed32, knots: &[(Fixed32, Fixed32)]) -> Fixed32 {
+    // Linearly interpolates between `(x, y)` knots sorted by `x`. Inputs
+    // outside the knots are clamped to the first or last `y`. All values
+    // must share one exponent
+    let exp = x.exp();
+    if knots
+        .iter()
+        .any(|(kx, ky)| kx.exp() != exp || ky.exp() != exp)
+    {
+        panic!(
+            "Only support piecewise linear approximation with the same \
+        exponential!"
+        )
+    }
+    let (first, last) = match (knots.first(), knots.last()) {
+        (Some(first), Some(last)) => (first, last),
+        _ => panic!("Piecewise linear approximation requires knots!"),
+    };
+    if x.value() <= first.0.value() {
+        return first.1;
+    }
+    if x.value() >= last.0.value() {
+        return last.1;
+    }
+
+    // The first knot to the right of `x` closes the enclosing interval
+    let i = knots.partition_point(|(kx, _)| kx.value() <= x.value());
+    let ((x0, y0), (x1, y1)) = (knots[i - 1], knots[i]);
+    interpolate(x, (x0, y0), (x1, y1))
+}
+
+pub fn piecewise_linear_encrypted(
+    x: TfheFixed32,
+    knots: &[(Fixed32, Fixed32)],
+) -> TfheFixed32 {
+    // Oblivious version of `piecewise_linear`: every segment is evaluated on
+    // the encrypted input, and a chain of encrypted comparisons keeps the one
+    // that encloses `x`. The segments cost one plaintext multiplication each,
+    // all at depth 1, but the n selections are sequential, so the depth grows
+    // linearly with the number of knots
+    let exp = x.exp() as i32;
+    if knots
+        .iter()
+        .any(|(kx, ky)| kx.exp() != exp || ky.exp() != exp)
+    {
+        panic!(
+            "Only support piecewise linear approximation with the same \
+        exponential!"
+        )
+    }
+    let (first, last) = match (knots.first(), knots.last()) {
+        (Some(first), Some(last)) => (*first, *last),
+        _ => panic!("Piecewise linear approximation requires knots!"),
+    };
+
+    // y0 + slope * (x - x0)
+    let line = |(x0, y0): (Fixed32, Fixed32), slope: Fixed32| {
+        x.clone()
+            .add_plaintext(-x0)
+            .mul_plaintext(slope)
+            .add_plaintext(y0)
+    };
+    let flat = Fixed32::new(0, exp);
+
+    let mut result = line(first, flat);
+    for segment in knots.windows(2) {
+        let ((x0, y0), (x1, y1)) = (segment[0], segment[1]);
+        let dy = (y1 - y0).value() as i64;
+        let dx = (x1 - x0).value() as i64;
+        let slope = Fixed32::new(((dy << exp) / dx) as i32, exp);
+
+        let cond = x.ge_plaintext(x0);
+        result = TfheFixed32::conditional_select(
+            &cond,
+            &line((x0, y0), slope),
+            &result,
+        );
+    }
+    let cond = x.ge_plaintext(last.0);
+
+    TfheFixed32::conditional_select(&cond, &line(last, flat), &result)
+}
+
+fn interpolate(
+    x: Fixed32,
+    (x0, y0): (Fixed32, Fixed32),
+    (x1, y1): (Fixed32, Fixed32),
+) -> Fixed32 {
+    // y0 + (y1 - y0) * (x - x0) / (x1 - x0), with the product and quotient
+    // computed exactly in 64 bits
+    let dy = (y1 - y0).value() as i64;
+    let dx = (x1 - x0).value() as i64;
+    let t = (x - x0).value() as i64;
+
+    Fixed32::new(y0.value() + (dy * t / dx) as i32, x.exp())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_eval_poly() {
+        // 1 + 2x + 3x^2 at x = 0.5
+        let coefficients = [
+            Fixed32::from(1., 16),
+            Fixed32::from(2., 16),
+            Fixed32::from(3., 16),
+        ];
+        let result = eval_poly(&coefficients, Fixed32::from(0.5, 16));
+        assert_eq!(result.to_f32(), 2.75);
+        assert_eq!(eval_poly(&[], Fixed32::from(0.5, 16)).to_f32(), 0.);
+    }
+
+    #[test]
+    fn test_chebyshev_reproduces_polynomial() {
+        let coefficients =
+            chebyshev_approximation(|x| x * x - 3. * x + 1., 2, -2., 5., 24);
+        let expected = [1., -3., 1.];
+        for (c, e) in coefficients.iter().zip(expected) {
+            assert!((c.to_f32() - e).abs() < 1e-6, "got {}", c.to_f32());
+        }
+    }
+
+    #[test]
+    fn test_chebyshev_sin() {
+        let coefficients = chebyshev_approximation(f64::sin, 9, 0., PI, 24);
+        assert_eq!(coefficients.len(), 10);
+
+        for i in 0..=100 {
+            let x = PI * i as f64 / 100.;
+            let result = eval_poly(&coefficients, Fixed32::from(x as f32, 24));
+            let error = (result.to_f32() as f64 - x.sin()).abs();
+            assert!(error < 0.001, "sin({}) off by {}", x, error);
+        }
+    }
+
+    #[test]
+    fn test_piecewise_linear() {
+        let knots = [
+            (Fixed32::from(-1., 16), Fixed32::from(2., 16)),
+            (Fixed32::from(0., 16), Fixed32::from(0., 16)),
+            (Fixed32::from(2., 16), Fixed32::from(1., 16)),
+        ];
+        let at =
+            |x: f32| piecewise_linear(Fixed32::from(x, 16), &knots).to_f32();
+
+        assert_eq!(at(-0.5), 1.);
+        assert_eq!(at(0.), 0.);
+        assert_eq!(at(1.5), 0.75);
+        assert_eq!(at(-3.), 2.);
+        assert_eq!(at(5.), 1.);
+    }
+
+    #[test]
+    fn test_piecewise_linear_sqrt() {
+        // 8 knots at x = 4 * (i / 7)^2, denser near 0 where sqrt curves most;
+        // the error peaks at a quarter of the first interval
+        let knots: Vec<(Fixed32, Fixed32)> = (0..8)
+            .map(|i| {
+                let x = 4. * (i as f32 / 7.).powi(2);
+                (Fixed32::from(x, 24), Fixed32::from(x.sqrt(), 24))
+            })
+            .collect();
+
+        let max_error = (0..=400)
+            .map(|i| {
+                let x = i as f32 / 100.;
+                let result = piecewise_linear(Fixed32::from(x, 24), &knots);
+                (result.to_f32() - x.sqrt()).abs()
+            })
+            .fold(0., f32::max);
+        assert!(max_error < 0.075, "max error {}", max_error);
+    }
+
+    #[test]
+    fn test_piecewise_linear_encrypted() {
+        use crate::test_utils::test_assert_encrypted_eq;
+        use tfhe::{
+            generate_keys,
+            set_server_key,
+            ConfigBuilder,
+        };
+
+        let config = ConfigBuilder::default().build();
+        let (client_key, server_key) = generate_keys(config);
+
+        set_server_key(server_key);
+        let knots: Vec<(Fixed32, Fixed32)> = (0..8)
+            .map(|i| {
+                let x = 4. * (i as f32 / 7.).powi(2);
+                (Fixed32::from(x, 24), Fixed32::from(x.sqrt(), 24))
+            })
+            .collect();
+
+        for x in [-1., 0., 0.02, 0.3, 1., 2.5, 3.99, 4., 6.] {
+            let encrypted = TfheFixed32::from(&client_key, x, 24);
+            let result = piecewise_linear_encrypted(encrypted, &knots);
+            let expected = piecewise_linear(Fixed32::from(x, 24), &knots);
+            // 1e-5 is about 168 ULPs at exponent 24
+            test_assert_encrypted_eq(
+                &client_key,
+                &result,
+                expected.to_f32(),
+                168,
+            );
+        }
+    }
+}
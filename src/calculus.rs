@@ -0,0 +1,160 @@
+use crate::fixed::Fixed32;
+
+pub fn newton_root(
+    f: impl Fn(Fixed32) -> Fixed32,
+    df: impl Fn(Fixed32) -> Fixed32,
+    x0: Fixed32,
+    tol: Fixed32,
+    max_iter: usize,
+) -> Option<Fixed32> {
+    // Iterates x <- x - f(x) / f'(x) until a step is no larger than `tol`.
+    // Returns `None` if that does not happen within `max_iter` iterations or
+    // the derivative vanishes. The division goes through `reciprocal`, so
+    // keep f'(x) of moderate size near the root
+    let mut x = x0;
+    for _ in 0..max_iter {
+        let slope = df(x);
+        if slope.value() == 0 {
+            return None;
+        }
+
+        let next = x - f(x) / slope;
+        if next.approx_eq(x, tol) {
+            return Some(next);
+        }
+        x = next;
+    }
+
+    None
+}
+
+pub fn integrate_trapezoid_slice(ys: &[Fixed32], dx: Fixed32) -> Fixed32 {
+    // dx * (y[0] / 2 + y[1] + ... + y[n - 2] + y[n - 1] / 2) for samples
+    // spaced `dx` apart. The sum is kept doubled in an `i64` so the halved end
+    // points lose nothing; fewer than two samples integrate to zero
+    let exp = dx.exp();
+    if ys.iter().any(|y| y.exp() != exp) {
+        panic!("Only support integrating samples with the same exponential as the step!")
+    }
+    if ys.len() < 2 {
+        return Fixed32::new(0, exp);
+    }
+
+    let twice_sum: i64 = 2 * ys.iter().map(|y| y.value() as i64).sum::<i64>()
+        - ys[0].value() as i64
+        - ys[ys.len() - 1].value() as i64;
+    let area = (twice_sum as i128 * dx.value() as i128) >> (exp + 1);
+
+    Fixed32::new(area as i32, exp)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f32::consts::{
+        FRAC_PI_2,
+        FRAC_PI_4,
+        PI,
+    };
+
+    #[test]
+    fn test_newton_root_sqrt_2() {
+        let two = Fixed32::from(2., 16);
+        let root = newton_root(
+            |x| x * x - two,
+            |x| two * x,
+            Fixed32::from(1., 16),
+            Fixed32::from(0.0001, 16),
+            20,
+        )
+        .unwrap();
+        assert!(
+            root.approx_eq(
+                Fixed32::from(2f32.sqrt(), 16),
+                Fixed32::from(1e-3, 16)
+            ),
+            "got {}",
+            root.to_f32()
+        );
+    }
+
+    #[test]
+    fn test_newton_root_cube_root_27() {
+        let three = Fixed32::from(3., 16);
+        let root = newton_root(
+            |x| x * x * x - Fixed32::from(27., 16),
+            |x| three * x * x,
+            Fixed32::from(4., 16),
+            Fixed32::from(0.0001, 16),
+            20,
+        )
+        .unwrap();
+        assert!(
+            root.approx_eq(three, Fixed32::from(1e-3, 16)),
+            "got {}",
+            root.to_f32()
+        );
+    }
+
+    #[test]
+    fn test_integrate_trapezoid_slice_triangle() {
+        // A triangle of height 1 over [0, pi]: the trapezoidal rule is exact
+        // for piecewise linear samples, giving pi / 4 * (0.5 + 1 + 0.5)
+        let ys = [0., 0.5, 1., 0.5, 0.].map(|y| Fixed32::from(y, 24));
+        let area = integrate_trapezoid_slice(&ys, Fixed32::from(FRAC_PI_4, 24));
+        assert!(
+            area.approx_eq(
+                Fixed32::from(FRAC_PI_2, 24),
+                Fixed32::from(1e-6, 24)
+            ),
+            "got {}",
+            area.to_f32()
+        );
+    }
+
+    #[test]
+    fn test_integrate_trapezoid_slice_sine() {
+        // The integral of sin over [0, pi] is 2
+        let n = 64;
+        let dx = PI / n as f32;
+        let ys: Vec<Fixed32> = (0..=n)
+            .map(|i| Fixed32::from((i as f32 * dx).sin(), 24))
+            .collect();
+        let area = integrate_trapezoid_slice(&ys, Fixed32::from(dx, 24));
+        assert!(
+            area.approx_eq(Fixed32::from(2., 24), Fixed32::from(1e-3, 24)),
+            "got {}",
+            area.to_f32()
+        );
+    }
+
+    #[test]
+    fn test_integrate_trapezoid_slice_short() {
+        let dx = Fixed32::from(0.5, 16);
+        assert_eq!(integrate_trapezoid_slice(&[], dx), Fixed32::new(0, 16));
+        assert_eq!(
+            integrate_trapezoid_slice(&[Fixed32::from(3., 16)], dx),
+            Fixed32::new(0, 16)
+        );
+    }
+
+    #[test]
+    fn test_newton_root_no_convergence() {
+        // x^2 + 1 has no real root
+        let one = Fixed32::from(1., 16);
+        let two = Fixed32::from(2., 16);
+        let root = newton_root(
+            |x| x * x + one,
+            |x| two * x,
+            Fixed32::from(0.5, 16),
+            Fixed32::from(0.0001, 16),
+            10,
+        );
+        assert_eq!(root, None);
+
+        // The derivative vanishes at the starting point
+        let zero = Fixed32::new(0, 16);
+        let root = newton_root(|x| x * x - two, |x| two * x, zero, one, 10);
+        assert_eq!(root, None);
+    }
+}
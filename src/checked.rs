@@ -0,0 +1,136 @@
+use crate::fixed::Fixed32;
+use std::ops::{
+    Add,
+    Mul,
+    Sub,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Fixed32Checked {
+    // The wrapped result of the operations so far
+    inner: Fixed32,
+
+    // Whether this value or any value it was computed from overflowed
+    overflowed: bool,
+}
+
+impl Fixed32Checked {
+    pub fn new(inner: Fixed32) -> Self {
+        Self {
+            inner,
+            overflowed: false,
+        }
+    }
+
+    pub fn inner(&self) -> Fixed32 {
+        self.inner
+    }
+
+    pub fn overflowed(&self) -> bool {
+        self.overflowed
+    }
+
+    fn from_wide(value: i64, exp: i32, overflowed: bool) -> Self {
+        // Wraps `value` to 32 bits like hardware would, and raises the flag
+        // if that lost any bits
+        Self {
+            inner: Fixed32::new(value as i32, exp),
+            overflowed: overflowed || i32::try_from(value).is_err(),
+        }
+    }
+
+    fn aligned(self, other: Self) -> (i64, i64, i32) {
+        // Aligns both values to the larger exponent in 64 bits
+        let exp = self.inner.exp().max(other.inner.exp());
+        let align = |x: Fixed32| (x.value() as i64) << (exp - x.exp());
+
+        (align(self.inner), align(other.inner), exp)
+    }
+}
+
+impl From<Fixed32> for Fixed32Checked {
+    fn from(value: Fixed32) -> Self {
+        Self::new(value)
+    }
+}
+
+impl Add for Fixed32Checked {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self::Output {
+        let (a, b, exp) = self.aligned(other);
+        Self::from_wide(a + b, exp, self.overflowed || other.overflowed)
+    }
+}
+
+impl Sub for Fixed32Checked {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self::Output {
+        let (a, b, exp) = self.aligned(other);
+        Self::from_wide(a - b, exp, self.overflowed || other.overflowed)
+    }
+}
+
+impl Mul for Fixed32Checked {
+    type Output = Self;
+
+    fn mul(self, other: Self) -> Self::Output {
+        if self.inner.exp() != other.inner.exp() {
+            panic!(
+                "Only support multiplication between two fixed-point \
+            numbers with the same exponential!"
+            )
+        }
+
+        let exp = self.inner.exp();
+        let product =
+            (self.inner.value() as i64 * other.inner.value() as i64) >> exp;
+        Self::from_wide(product, exp, self.overflowed || other.overflowed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_no_overflow() {
+        let a = Fixed32Checked::new(Fixed32::from(1.5, 16));
+        let b = Fixed32Checked::new(Fixed32::from(-2.25, 16));
+
+        assert_eq!((a + b).inner(), Fixed32::from(-0.75, 16));
+        assert_eq!((a - b).inner(), Fixed32::from(3.75, 16));
+        assert_eq!((a * b).inner(), Fixed32::from(-3.375, 16));
+        assert!(!(a * b - a + b).overflowed());
+    }
+
+    #[test]
+    fn test_overflow_propagates() {
+        // Only the second operation leaves the Q16.16 range
+        let a = Fixed32Checked::new(Fixed32::from(200., 16));
+        let b = Fixed32Checked::new(Fixed32::from(100., 16));
+        let c = Fixed32Checked::new(Fixed32::from(1., 16));
+
+        let first = a + b;
+        assert!(!first.overflowed());
+
+        let second = first * a;
+        assert!(second.overflowed());
+
+        let third = second - c;
+        assert!(third.overflowed());
+        assert!(!c.overflowed());
+    }
+
+    #[test]
+    fn test_add_overflow_at_boundary() {
+        let max = Fixed32Checked::new(Fixed32::new(i32::MAX, 24));
+        let ulp = Fixed32Checked::new(Fixed32::new(1, 24));
+
+        let sum = max + ulp;
+        assert!(sum.overflowed());
+        assert_eq!(sum.inner(), Fixed32::new(i32::MIN, 24));
+        assert!(!(max - ulp).overflowed());
+    }
+}
@@ -0,0 +1,58 @@
+use crate::fixed::Fixed32;
+
+pub fn bit_reverse_index(n: usize, log2_n: usize) -> usize {
+    // Reverses the lowest `log2_n` bits of `n`; higher bits are dropped
+    if log2_n == 0 {
+        return 0;
+    }
+
+    n.reverse_bits() >> (usize::BITS as usize - log2_n)
+}
+
+pub fn bit_reverse_permute(values: &mut [Fixed32]) {
+    // Reorders `values` in place so that index `i` moves to
+    // `bit_reverse_index(i, log2(len))`, as the iterative Cooley-Tukey FFT
+    // expects. The length must be a power of two
+    if !values.len().is_power_of_two() {
+        panic!("The length of a bit-reversal permutation must be a power of 2!")
+    }
+
+    let log2_n = values.len().trailing_zeros() as usize;
+    for i in 0..values.len() {
+        // Every pair is swapped once, from its smaller index
+        let j = bit_reverse_index(i, log2_n);
+        if i < j {
+            values.swap(i, j);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bit_reverse_index() {
+        assert_eq!(bit_reverse_index(0b001, 3), 0b100);
+        assert_eq!(bit_reverse_index(0b110, 3), 0b011);
+        assert_eq!(bit_reverse_index(0b1011, 4), 0b1101);
+        assert_eq!(bit_reverse_index(5, 0), 0);
+    }
+
+    #[test]
+    fn test_bit_reverse_permute() {
+        let mut values: Vec<Fixed32> =
+            (0..8).map(|i| Fixed32::from(i as f32, 16)).collect();
+        bit_reverse_permute(&mut values);
+
+        let expected: Vec<Fixed32> = [0, 4, 2, 6, 1, 5, 3, 7]
+            .iter()
+            .map(|&i| Fixed32::from(i as f32, 16))
+            .collect();
+        assert_eq!(values, expected);
+
+        // The permutation is its own inverse
+        bit_reverse_permute(&mut values);
+        assert_eq!(values[3], Fixed32::from(3., 16));
+    }
+}
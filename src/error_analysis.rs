@@ -0,0 +1,183 @@
+use crate::fixed::Fixed32;
+use std::ops::{
+    Add,
+    Mul,
+    Sub,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Fixed32WithError {
+    // The computed fixed-point value
+    value: Fixed32,
+
+    // An upper bound on the distance between `value` and the exact result
+    absolute_error_bound: Fixed32,
+}
+
+impl Fixed32WithError {
+    pub fn new(value: Fixed32, absolute_error_bound: Fixed32) -> Self {
+        Self {
+            value,
+            absolute_error_bound: absolute_error_bound.abs(),
+        }
+    }
+
+    pub fn exact(value: Fixed32) -> Self {
+        Self::new(value, Fixed32::new(0, value.exp()))
+    }
+
+    pub fn from(value: f32, exp: i32) -> Self {
+        // Converting from `f32` rounds to the nearest step, so the result is
+        // off by at most half a unit in the last place. The bound shares the
+        // value's exponent, where half a unit cannot be represented, so it
+        // is rounded up to one
+        Self::new(Fixed32::from(value, exp), Fixed32::new(1, exp))
+    }
+
+    pub fn value(&self) -> Fixed32 {
+        self.value
+    }
+
+    pub fn absolute_error_bound(&self) -> Fixed32 {
+        self.absolute_error_bound
+    }
+}
+
+impl Add for Fixed32WithError {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self::Output {
+        // |err_a| + |err_b|
+        Self {
+            value: self.value + other.value,
+            absolute_error_bound: self.absolute_error_bound
+                + other.absolute_error_bound,
+        }
+    }
+}
+
+impl Sub for Fixed32WithError {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self::Output {
+        // Subtraction propagates errors exactly like addition
+        Self {
+            value: self.value - other.value,
+            absolute_error_bound: self.absolute_error_bound
+                + other.absolute_error_bound,
+        }
+    }
+}
+
+impl Mul for Fixed32WithError {
+    type Output = Self;
+
+    fn mul(self, other: Self) -> Self::Output {
+        // |a| * |err_b| + |b| * |err_a| + |err_a| * |err_b|, plus one unit in
+        // the last place for the truncating fixed-point product. Each term is
+        // computed in i64 and rounded up, since truncating it like the
+        // product itself would make the bound too small
+        let exp = self.value.exp();
+        if [
+            other.value,
+            self.absolute_error_bound,
+            other.absolute_error_bound,
+        ]
+        .iter()
+        .any(|x| x.exp() != exp)
+        {
+            panic!("Only support multiplying fixed-point numbers with errors of the same exponential!")
+        }
+
+        let (a, b) = (
+            (self.value.value() as i64).abs(),
+            (other.value.value() as i64).abs(),
+        );
+        let (err_a, err_b) = (
+            self.absolute_error_bound.value() as i64,
+            other.absolute_error_bound.value() as i64,
+        );
+        let round_up = |p: i64| (p + (1 << exp) - 1) >> exp;
+        let bound = round_up(a * err_b)
+            + round_up(b * err_a)
+            + round_up(err_a * err_b)
+            + 1;
+
+        Self {
+            value: self.value * other.value,
+            absolute_error_bound: Fixed32::new(
+                i32::try_from(bound).expect(
+                    "The error bound does not fit in a fixed-point number!",
+                ),
+                exp,
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_add_sub_bounds() {
+        let a =
+            Fixed32WithError::new(Fixed32::from(2., 16), Fixed32::new(3, 16));
+        let b =
+            Fixed32WithError::new(Fixed32::from(1., 16), Fixed32::new(-2, 16));
+
+        assert_eq!((a + b).absolute_error_bound(), Fixed32::new(5, 16));
+        assert_eq!((a - b).absolute_error_bound(), Fixed32::new(5, 16));
+        assert_eq!((a - b).value().to_f32(), 1.);
+    }
+
+    #[test]
+    fn test_mul_bound() {
+        let a = Fixed32WithError::new(
+            Fixed32::from(2., 16),
+            Fixed32::from(0.5, 16),
+        );
+        let b = Fixed32WithError::new(
+            Fixed32::from(-3., 16),
+            Fixed32::from(0.25, 16),
+        );
+
+        // 2 * 0.25 + 3 * 0.5 + 0.5 * 0.25 + 2^-16
+        let bound = (a * b).absolute_error_bound().to_f32();
+        assert_eq!(bound, 2.125 + 1. / 65536.);
+    }
+
+    #[test]
+    fn test_mul_bound_rounds_up() {
+        // 0.3 * 2^-24 is a fraction of a unit in the last place, which a
+        // truncating product would drop from the bound
+        let a = Fixed32WithError::exact(Fixed32::from(0.3, 24));
+        let b =
+            Fixed32WithError::new(Fixed32::from(1., 24), Fixed32::new(1, 24));
+
+        assert_eq!((a * b).absolute_error_bound(), Fixed32::new(2, 24));
+        assert_eq!((b * a).absolute_error_bound(), Fixed32::new(2, 24));
+    }
+
+    #[test]
+    fn test_newton_raphson_error() {
+        // Traces x <- x * (2 - d * x) for 1 / 3.15 and checks the computed
+        // value stays within the propagated bound of the exact iteration
+        let divisor = 3.15;
+        let d = Fixed32WithError::from(divisor, 24);
+        let two = Fixed32WithError::exact(Fixed32::from(2., 24));
+        let mut x = Fixed32WithError::exact(Fixed32::from(0.5, 24));
+        let mut exact = 0.5f64;
+
+        for _ in 0..5 {
+            x = x * (two - d * x);
+            exact = exact * (2. - divisor as f64 * exact);
+
+            let error = (x.value().to_f32() as f64 - exact).abs();
+            let bound = x.absolute_error_bound().to_f32() as f64;
+            assert!(error <= bound, "error {} exceeds bound {}", error, bound);
+        }
+
+        assert!(x.absolute_error_bound().to_f32() < 1e-3);
+    }
+}
@@ -0,0 +1,97 @@
+use crate::fixed::Fixed32;
+
+// Mirrors the memory layout of `Fixed32` for use from C. Run
+// `cargo build --features ffi` to regenerate
+// `include/fixed_point_arithmetic.h`.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Fixed32Ffi {
+    pub value: i32,
+    pub exp: i32,
+}
+
+impl From<Fixed32> for Fixed32Ffi {
+    fn from(value: Fixed32) -> Self {
+        Fixed32Ffi {
+            value: value.value(),
+            exp: value.exp(),
+        }
+    }
+}
+
+impl From<Fixed32Ffi> for Fixed32 {
+    fn from(value: Fixed32Ffi) -> Self {
+        Fixed32::new(value.value, value.exp)
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn fixed32_new(value: i32, exp: i32) -> Fixed32Ffi {
+    Fixed32::new(value, exp).into()
+}
+
+#[no_mangle]
+pub extern "C" fn fixed32_from_f32(value: f32, exp: i32) -> Fixed32Ffi {
+    Fixed32::from(value, exp).into()
+}
+
+#[no_mangle]
+pub extern "C" fn fixed32_to_f32(x: Fixed32Ffi) -> f32 {
+    let x: Fixed32 = x.into();
+    x.to_f32()
+}
+
+#[no_mangle]
+pub extern "C" fn fixed32_add(a: Fixed32Ffi, b: Fixed32Ffi) -> Fixed32Ffi {
+    let (a, b): (Fixed32, Fixed32) = (a.into(), b.into());
+    (a + b).into()
+}
+
+#[no_mangle]
+pub extern "C" fn fixed32_mul(a: Fixed32Ffi, b: Fixed32Ffi) -> Fixed32Ffi {
+    // Aborts the process if the exponents differ, since panics cannot unwind
+    // into C
+    let (a, b): (Fixed32, Fixed32) = (a.into(), b.into());
+    (a * b).into()
+}
+
+#[no_mangle]
+pub extern "C" fn fixed32_reciprocal(x: Fixed32Ffi) -> Fixed32Ffi {
+    let x: Fixed32 = x.into();
+    x.reciprocal().into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let x = Fixed32::from(3.15, 24);
+        let ffi: Fixed32Ffi = x.into();
+        assert_eq!((ffi.value, ffi.exp), (x.value(), 24));
+
+        let back: Fixed32 = ffi.into();
+        assert_eq!(back, x);
+    }
+
+    #[test]
+    fn test_extern_functions() {
+        let a = fixed32_from_f32(2.5, 24);
+        let b = fixed32_from_f32(4., 24);
+        assert_eq!(fixed32_to_f32(fixed32_add(a, b)), 6.5);
+        assert_eq!(fixed32_to_f32(fixed32_mul(a, b)), 10.);
+        assert!((fixed32_to_f32(fixed32_reciprocal(b)) - 0.25).abs() < 1e-4);
+    }
+
+    #[cfg(feature = "ffi")]
+    #[test]
+    fn test_c_snippet() {
+        // Compiled from `tests/c/ffi_smoke.c` by the build script
+        extern "C" {
+            fn fixed32_ffi_smoke_test() -> std::ffi::c_int;
+        }
+
+        assert_eq!(unsafe { fixed32_ffi_smoke_test() }, 0);
+    }
+}
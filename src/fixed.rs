@@ -1,12 +1,48 @@
-use std::ops::{
-    Add,
-    Div,
-    Mul,
-    Sub,
+use std::{
+    collections::HashMap,
+    fmt,
+    iter::{
+        Product,
+        Sum,
+    },
+    ops::{
+        Add,
+        BitAnd,
+        BitOr,
+        BitXor,
+        Div,
+        Mul,
+        Neg,
+        Not,
+        Sub,
+    },
 };
 use tfhe::core_crypto::prelude::SignedInteger;
 
-#[derive(Debug, Clone, Copy)]
+// π / 180 with 62 fractional bits
+const RADIANS_PER_DEGREE_Q62: i128 = 0x011D_F46A_2529_D391;
+
+// 180 / π with 56 fractional bits
+const DEGREES_PER_RADIAN_Q56: i128 = 0x394B_B834_C783_EF71;
+
+// Exponent of the entries in `RECIPROCAL_TABLE`
+const RECIPROCAL_TABLE_EXP: i32 = 24;
+
+// `1 / n` rounded to `RECIPROCAL_TABLE_EXP` bits for every `n` in [1, 255];
+// entry 0 is unused
+const RECIPROCAL_TABLE: [i32; 256] = {
+    let mut table = [0; 256];
+    let mut n = 1;
+    while n < 256 {
+        table[n] = ((1 << RECIPROCAL_TABLE_EXP) + n as i32 / 2) / n as i32;
+        n += 1;
+    }
+    table
+};
+
+#[cfg_attr(feature = "wasm", wasm_bindgen::prelude::wasm_bindgen)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(C)]
 pub struct Fixed32 {
     // Stores the integer representing of the fixed-point value. The
     // fixed-point representation is scaled based on the `exp` field.
@@ -17,40 +53,163 @@ pub struct Fixed32 {
     exp: i32,
 }
 
+pub const fn valid_exp(exp: i32) -> bool {
+    // `1 << exp` misbehaves from 31 on: it is `i32::MIN` at 31 and overflows
+    // after that. Raw values built with `Fixed32::new` may still use other
+    // exponents (e.g. Q1.31), but `from`, `to_f32` and the arithmetic
+    // operators expect this range
+    exp >= 0 && exp < 31
+}
+
 impl Fixed32 {
-    pub fn new(value: i32, exp: i32) -> Self {
+    pub const fn new(value: i32, exp: i32) -> Self {
         Self { value, exp }
     }
 
+    pub fn value(self) -> i32 {
+        self.value
+    }
+
+    pub fn exp(self) -> i32 {
+        self.exp
+    }
+
     pub fn from<T: Into<f32>>(value: T, exp: i32) -> Self {
-        // Converts a floating-point number into a fixed-point number
-        let val: f32 = value.into() * (1 << exp) as f32;
+        // Converts a floating-point number into a fixed-point number. Scaling
+        // by a power of two is exact in `f64`, so this rounds the same way as
+        // computing in `f32`, but saturates out-of-range values
+        debug_assert!(
+            valid_exp(exp),
+            "The exponential must be between 0 and 30!"
+        );
+        Self::from_f64_clamped(value.into() as f64, exp)
+    }
+
+    pub fn from_f64_clamped(value: f64, exp: i32) -> Self {
+        // Clamps the scaled value to the `i32` range before casting, so
+        // infinities and values that are too large saturate; NaN becomes 0
+        let scaled = (value * 2f64.powi(exp)).round();
+        let clamped = scaled.clamp(i32::MIN as f64, i32::MAX as f64);
         Self {
-            value: val.round() as i32,
+            value: if clamped.is_nan() { 0 } else { clamped as i32 },
             exp,
         }
     }
 
+    pub fn from_scientific_notation(
+        mantissa: f32,
+        base10_exp: i32,
+        fixed_exp: i32,
+    ) -> Self {
+        // Converts `mantissa * 10^base10_exp`, scaling in `f64` so the power
+        // of ten does not round through `f32` first. Like
+        // `from_f64_clamped`, it accepts exponents above 30 for values that
+        // are only used as raw integers (see `valid_exp`)
+        let value = mantissa as f64 * 10f64.powi(base10_exp);
+        Self::from_f64_clamped(value, fixed_exp)
+    }
+
+    pub fn from_angle_degrees(degrees: f32, exp: i32) -> Self {
+        // Multiplies by a 62-bit fixed-point π / 180 instead of computing
+        // `degrees * π / 180` in `f32`, which loses 3-4 bits. The `f32` to
+        // `f64` scaling is exact, so only the final rounding remains
+        let degrees = (degrees as f64 * (1u64 << exp) as f64).round() as i128;
+        let radians = (degrees * RADIANS_PER_DEGREE_Q62 + (1 << 61)) >> 62;
+        let value = i32::try_from(radians)
+            .expect("The angle does not fit in a fixed-point number!");
+
+        Self { value, exp }
+    }
+
+    pub fn to_degrees(self) -> Self {
+        // Treats `self` as radians; panics if the angle in degrees overflows
+        // at the same exponent
+        let degrees =
+            (self.value as i128 * DEGREES_PER_RADIAN_Q56 + (1 << 55)) >> 56;
+        let value = i32::try_from(degrees)
+            .expect("The angle does not fit in a fixed-point number!");
+
+        Self {
+            value,
+            exp: self.exp,
+        }
+    }
+
     pub fn to_f32(self) -> f32 {
         // Converts a fixed-point number to a floating-point number
+        debug_assert!(
+            valid_exp(self.exp),
+            "The exponential must be between 0 and 30!"
+        );
         self.value as f32 / (1 << self.exp) as f32
     }
 
+    pub fn count_significant_bits(self) -> u32 {
+        // Counts the bits below and including the leading 1. Negative values
+        // have the sign bit set and therefore use all 32 bits.
+        32 - self.value.leading_zeros()
+    }
+
     pub fn get_leading_one_index(self) -> i32 {
         // Find the leading 1 in the name value using bitwise operations
-        let mut i = 31;
-        while i > 0 {
-            if (1 << i) & self.value > 0 {
-                return i;
-            }
-            i -= 1;
+        (self.count_significant_bits() as i32 - 1).max(0)
+    }
+
+    pub fn leading_significant_bit(self) -> u32 {
+        // Index of the leading 1 in the magnitude, so negative values are not
+        // stuck at the sign bit like `get_leading_one_index`. Zero gives 0
+        31u32.saturating_sub(self.value.unsigned_abs().leading_zeros())
+    }
+
+    pub fn is_power_of_two(self) -> bool {
+        // Checks the raw value, so 2^-3 at exp 16 counts but 3.0 does not.
+        // Dividing by such a value is a shift
+        self.value > 0 && (self.value & (self.value - 1)) == 0
+    }
+
+    pub fn nearest_power_of_two(self) -> Fixed32 {
+        // Rounds to the nearest power of two on a linear scale, with ties
+        // going up. 2^31 does not fit in an `i32`, so values above 1.5 * 2^30
+        // saturate to 2^30
+        if self.value <= 0 {
+            panic!("Only positive fixed-point numbers have a nearest power of two!")
+        }
+
+        let k = self.leading_significant_bit();
+        let round_up = k > 0 && self.value & (1 << (k - 1)) != 0;
+        let k = if round_up { (k + 1).min(30) } else { k };
+
+        Fixed32::new(1 << k, self.exp)
+    }
+
+    pub fn to_normalized_mantissa_exp(self) -> (f32, i32) {
+        // Like `frexp`: returns (m, e) with self ~= m * 2^e and
+        // 0.5 <= |m| < 1, or (0, 0) for zero. `m` is rounded to `f32`
+        if self.value == 0 {
+            return (0., 0);
         }
 
-        0
+        let bits = 32 - self.value.unsigned_abs().leading_zeros() as i32;
+        let mantissa = (self.value as f64 / 2f64.powi(bits)) as f32;
+        // More than 24 significant bits can round the mantissa up to 1
+        if mantissa.abs() == 1. {
+            (mantissa / 2., bits - self.exp + 1)
+        } else {
+            (mantissa, bits - self.exp)
+        }
     }
 
     pub fn reciprocal(self) -> Self {
-        let leading_one_index = self.get_leading_one_index();
+        // Newton-Raphson from a power-of-two guess. Writing the input as
+        // m * 2^k with m in [1, 2), the result is within 1e-6 relative error
+        // for m < 1.5 and within 0.1% for m < 1.75, but degrades to about 3%
+        // at m = 1.9 and is badly wrong as m approaches 2 (see
+        // `measure::worst_case_error`). Negative inputs use 1 / x = -(1 / -x)
+        if self.value < 0 {
+            return -(-self).reciprocal();
+        }
+
+        let leading_one_index = self.leading_significant_bit() as i32;
         let guess: i32 = 1 << (self.exp * 2 - leading_one_index);
 
         // Apply Newton-Raphson method
@@ -63,240 +222,2781 @@ impl Fixed32 {
 
         result
     }
-}
 
-impl Add for Fixed32 {
-    type Output = Fixed32;
+    pub fn approx_reciprocal_table(self) -> Option<Fixed32> {
+        // Looks up `1 / self` when `self` is an integer in [1, 255] and the
+        // exponent is at most `RECIPROCAL_TABLE_EXP`; the entry is rounded to
+        // `self.exp` bits. Returns `None` for every other input
+        if self.exp < 0 || self.exp > RECIPROCAL_TABLE_EXP {
+            return None;
+        }
+        let fraction_mask = (1i32 << self.exp) - 1;
+        if self.value & fraction_mask != 0 {
+            return None;
+        }
+        let n = self.value >> self.exp;
+        if !(1..=255).contains(&n) {
+            return None;
+        }
 
-    fn add(self, other: Self) -> Self::Output {
-        if self.exp == other.exp {
-            Fixed32::new(self.value + other.value, self.exp)
-        } else if self.exp > other.exp {
-            let shift = self.exp - other.exp;
-            Fixed32::new(self.value + (other.value << shift), self.exp)
-        } else {
-            let shift = other.exp - self.exp;
-            Fixed32::new((self.value << shift) + other.value, other.exp)
+        let shift = RECIPROCAL_TABLE_EXP - self.exp;
+        let entry = RECIPROCAL_TABLE[n as usize];
+        let value = match shift {
+            0 => entry,
+            _ => (entry + (1 << (shift - 1))) >> shift,
+        };
+
+        Some(Fixed32::new(value, self.exp))
+    }
+
+    pub fn precompute_reciprocal_table(
+        divisors: &[Fixed32],
+    ) -> HashMap<i32, Fixed32> {
+        // Computes `reciprocal` once per distinct divisor. The table is keyed
+        // by the raw value, so all divisors must share one exponential. A
+        // `HashMap` lookup costs about as much as the five Newton-Raphson
+        // iterations (see `bench_cached_div`), so this mainly guarantees
+        // that repeated divisions by the same divisor agree
+        if divisors.windows(2).any(|w| w[0].exp != w[1].exp) {
+            panic!("Only support reciprocal tables of fixed-point numbers with the same exponential!")
+        }
+
+        let mut table = HashMap::new();
+        for divisor in divisors {
+            table
+                .entry(divisor.value)
+                .or_insert_with(|| divisor.reciprocal());
         }
+
+        table
     }
-}
 
-impl Sub for Fixed32 {
-    type Output = Fixed32;
+    pub fn cached_div(
+        numerator: Fixed32,
+        divisor: Fixed32,
+        table: &HashMap<i32, Fixed32>,
+    ) -> Fixed32 {
+        // `numerator / divisor` with the reciprocal taken from a table built
+        // by `precompute_reciprocal_table`, falling back to computing it
+        let reciprocal = match table.get(&divisor.value) {
+            Some(&reciprocal) if reciprocal.exp == divisor.exp => reciprocal,
+            _ => divisor.reciprocal(),
+        };
+        numerator * reciprocal
+    }
 
-    fn sub(self, other: Self) -> Self::Output {
-        if self.exp == other.exp {
-            Fixed32::new(self.value - other.value, self.exp)
-        } else if self.exp > other.exp {
-            let shift = self.exp - other.exp;
-            Fixed32::new(self.value - (other.value << shift), self.exp)
-        } else {
-            let shift = other.exp - self.exp;
-            Fixed32::new((self.value << shift) - other.value, other.exp)
+    pub fn from_binary_str(s: &str, exp: i32) -> Result<Self, ParseFixedError> {
+        // Parses a binary literal such as "1011.01" or "-0.1"; fractional
+        // digits beyond `exp` are truncated
+        let (negative, digits) = match s.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, s),
+        };
+        let (integer_part, fractional_part) =
+            digits.split_once('.').unwrap_or((digits, ""));
+        if integer_part.is_empty() && fractional_part.is_empty() {
+            return Err(ParseFixedError::Empty);
+        }
+
+        let mut magnitude: i64 = 0;
+        for c in integer_part.chars() {
+            magnitude = (magnitude << 1) | parse_binary_digit(c)?;
+            if magnitude > 1 << 31 {
+                return Err(ParseFixedError::Overflow);
+            }
+        }
+        magnitude <<= exp;
+
+        for (i, c) in fractional_part.chars().enumerate() {
+            let bit = parse_binary_digit(c)?;
+            let position = exp - 1 - i as i32;
+            if position >= 0 {
+                magnitude |= bit << position;
+            }
+        }
+
+        let value = if negative { -magnitude } else { magnitude };
+        match i32::try_from(value) {
+            Ok(value) => Ok(Fixed32::new(value, exp)),
+            Err(_) => Err(ParseFixedError::Overflow),
         }
     }
-}
 
-impl Mul for Fixed32 {
-    type Output = Fixed32;
+    pub fn from_decimal_string_lossless(
+        s: &str,
+        exp: i32,
+    ) -> Result<Self, ParseFixedError> {
+        // Parses a decimal literal such as "-12.375" with integer arithmetic
+        // only, rounding to the nearest multiple of 2^-exp. Going through
+        // `f32` first rounds twice, and "0.1" is already off in the 8th
+        // digit. Fractional digits beyond the 19th are checked but ignored
+        let (negative, digits) = match s.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, s),
+        };
+        let (integer_part, fractional_part) =
+            digits.split_once('.').unwrap_or((digits, ""));
+        if integer_part.is_empty() && fractional_part.is_empty() {
+            return Err(ParseFixedError::Empty);
+        }
 
-    fn mul(self, other: Self) -> Self::Output {
-        if self.exp != other.exp {
-            panic!(
-                "Only support multiplication between two fixed-point \
-            numbers with the same exponential!"
-            )
+        let mut integer: u64 = 0;
+        for c in integer_part.chars() {
+            let digit =
+                c.to_digit(10).ok_or(ParseFixedError::InvalidDigit(c))?;
+            integer = integer * 10 + digit as u64;
+            if integer > 1 << 31 {
+                return Err(ParseFixedError::Overflow);
+            }
         }
 
-        let val1: i64 = self.value as i64;
-        let val2: i64 = other.value as i64;
-        let product: i64 = (val1 * val2) >> self.exp;
+        // `numerator / denominator` is the fractional part, exactly
+        let (mut numerator, mut denominator): (u128, u128) = (0, 1);
+        for (i, c) in fractional_part.chars().enumerate() {
+            let digit =
+                c.to_digit(10).ok_or(ParseFixedError::InvalidDigit(c))?;
+            if i < 19 {
+                numerator = numerator * 10 + digit as u128;
+                denominator *= 10;
+            }
+        }
+        let fraction = ((numerator << exp) + denominator / 2) / denominator;
 
-        Fixed32 {
-            value: product as i32,
-            exp: self.exp,
+        let magnitude = ((integer as u128) << exp) + fraction;
+        let value = match negative {
+            true => -(magnitude as i128),
+            false => magnitude as i128,
+        };
+        match i32::try_from(value) {
+            Ok(value) => Ok(Fixed32::new(value, exp)),
+            Err(_) => Err(ParseFixedError::Overflow),
         }
     }
-}
 
-impl Div for Fixed32 {
-    type Output = Fixed32;
+    pub fn from_str_radix(
+        s: &str,
+        radix: u32,
+        exp: i32,
+    ) -> Result<Self, ParseFixedError> {
+        // Parses the raw scaled integer in radix 2, 8, 10 or 16, as register
+        // dumps and datasheets print it: `from_str_radix("0x00018000", 16,
+        // 16)` is 1.5. A "0x", "0o" or "0b" prefix matching the radix is
+        // allowed. Outside radix 10 the digits are a 32-bit two's complement
+        // pattern, so "0xFFFF8000" is negative
+        let prefix = match radix {
+            2 => "0b",
+            8 => "0o",
+            10 => "",
+            16 => "0x",
+            _ => return Err(ParseFixedError::UnsupportedRadix(radix)),
+        };
+        let (negative, digits) = match s.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, s),
+        };
+        let digits = match digits.get(..prefix.len()) {
+            Some(head)
+                if !prefix.is_empty() && head.eq_ignore_ascii_case(prefix) =>
+            {
+                &digits[prefix.len()..]
+            }
+            _ => digits,
+        };
+        if digits.is_empty() {
+            return Err(ParseFixedError::Empty);
+        }
 
-    fn div(self, other: Self) -> Self::Output {
-        if self.exp != other.exp {
-            panic!(
-                "Only support multiplication between two fixed-point \
-            numbers with the same exponential!"
-            )
+        let mut magnitude: u64 = 0;
+        for c in digits.chars() {
+            let digit =
+                c.to_digit(radix).ok_or(ParseFixedError::InvalidDigit(c))?;
+            magnitude = magnitude * radix as u64 + digit as u64;
+            if magnitude > u32::MAX as u64 {
+                return Err(ParseFixedError::Overflow);
+            }
         }
 
-        if other.value == 0 {
-            panic!("Division by zero error!");
+        let value = match (negative, radix) {
+            (true, _) => -(magnitude as i64),
+            (false, 10) => magnitude as i64,
+            (false, _) => magnitude as u32 as i32 as i64,
+        };
+        match i32::try_from(value) {
+            Ok(value) => Ok(Fixed32::new(value, exp)),
+            Err(_) => Err(ParseFixedError::Overflow),
         }
+    }
 
-        // Not accurate
-        // let quotient = self.value / other.value * (1 << self.exp);
-        // Self::new(quotient, self.exp)
+    pub fn to_bit_vector(self) -> [bool; 32] {
+        // Decomposes the raw two's complement bits; index 0 is the LSB
+        std::array::from_fn(|i| (self.value >> i) & 1 == 1)
+    }
 
-        // Not accurate when `other` is greater than 1
-        self * other.reciprocal()
+    pub fn from_bit_vector(bits: [bool; 32], exp: i32) -> Fixed32 {
+        // Inverse of `to_bit_vector`; index 31 is the sign bit
+        let value = bits
+            .iter()
+            .enumerate()
+            .fold(0u32, |acc, (i, &bit)| acc | ((bit as u32) << i));
+        Fixed32::new(value as i32, exp)
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::measure::diff;
+    pub fn to_binary_str(self) -> String {
+        // Formats the raw bits as a binary literal with `exp` digits after the
+        // point, e.g. "-11.0100"
+        let (sign, integer_part, fractional_part) = self.split_magnitude();
+        if self.exp == 0 {
+            return format!("{}{:b}", sign, integer_part);
+        }
 
-    #[test]
-    fn test_add_same_exp() {
-        let a = Fixed32::new(10, 4);
-        let b = Fixed32::new(15, 4);
-        let result = a + b;
-        assert_eq!(result.value, 25);
-        assert_eq!(result.exp, 4);
+        format!(
+            "{}{:b}.{:0width$b}",
+            sign,
+            integer_part,
+            fractional_part,
+            width = self.exp as usize
+        )
     }
 
-    #[test]
-    fn test_add_different_exp() {
-        let a = Fixed32::new(10, 3);
-        let b = Fixed32::new(15, 2);
-        let result = a + b;
-        assert_eq!(result.value, 40);
-        assert_eq!(result.exp, 3);
+    pub fn to_hex_str(self) -> String {
+        // Formats the raw bits as a hexadecimal literal; the fractional bits
+        // are left-aligned to whole hexadecimal digits
+        let (sign, integer_part, fractional_part) = self.split_magnitude();
+        if self.exp == 0 {
+            return format!("{}{:x}", sign, integer_part);
+        }
+
+        let digits = (self.exp as usize).div_ceil(4);
+        let padding = digits * 4 - self.exp as usize;
+        format!(
+            "{}{:x}.{:0width$x}",
+            sign,
+            integer_part,
+            fractional_part << padding,
+            width = digits
+        )
     }
 
-    #[test]
-    fn test_sub_same_exp() {
-        let a = Fixed32::new(20, 4);
-        let b = Fixed32::new(10, 4);
-        let result = a - b;
-        assert_eq!(result.value, 10);
-        assert_eq!(result.exp, 4);
+    pub fn shift_left(self, n: u32) -> Option<Self> {
+        // Multiplies by 2^n while keeping `exp`; returns `None` on overflow
+        if n >= 32 {
+            return if self.value == 0 { Some(self) } else { None };
+        }
+
+        let shifted = (self.value as i64) << n;
+        i32::try_from(shifted)
+            .ok()
+            .map(|value| Fixed32::new(value, self.exp))
     }
 
-    #[test]
-    fn test_sub_different_exp() {
-        let a = Fixed32::new(40, 5);
-        let b = Fixed32::new(15, 3);
-        let result = a - b;
-        assert_eq!(result.value, -20);
-        assert_eq!(result.exp, 5);
+    pub fn shift_right(self, n: u32) -> Self {
+        // Divides by 2^n while keeping `exp`, rounding toward negative
+        // infinity
+        let value = self.value >> n.min(31);
+        Fixed32::new(value, self.exp)
     }
 
-    #[test]
-    fn test_mul_same_exp() {
-        // (10 * 20) >> 4 = 200 >> 4 = 12
-        let a = Fixed32::from(2.47, 24);
-        let b = Fixed32::from(3.19, 24);
-        let result = a * b;
-        assert_eq!(result.to_f32(), 7.8793);
-        assert_eq!(result.exp, 24);
+    pub fn scale_by_integer(self, n: i32) -> Self {
+        // Multiplies by an integer constant without a fixed-point multiply,
+        // saturating on overflow
+        Fixed32::new(self.value.saturating_mul(n), self.exp)
     }
 
-    #[test]
-    #[should_panic]
-    fn test_mul_different_exp() {
-        let a = Fixed32::new(10, 4);
-        let b = Fixed32::new(20, 3);
-        let _result = a * b;
+    pub fn bit_and(self, mask: i32) -> Self {
+        // Bitwise operations act on the raw scaled integer, not on the
+        // mathematical value; e.g. `x.bit_and(!((1 << x.exp) - 1))` clears the
+        // fractional bits
+        Fixed32::new(self.value & mask, self.exp)
     }
 
-    #[test]
-    fn test_div_divisible() {
-        let a = 20.;
-        let b = 5.;
-        let a_fixed = Fixed32::from(a, 5);
-        let b_fixed = Fixed32::from(b, 5);
-        let result = a_fixed / b_fixed;
-        println!("{}", result.value);
+    pub fn bit_or(self, mask: i32) -> Self {
+        Fixed32::new(self.value | mask, self.exp)
+    }
 
-        let result_float = result.to_f32();
-        let expected_result = a / b;
+    pub fn bit_xor(self, mask: i32) -> Self {
+        Fixed32::new(self.value ^ mask, self.exp)
+    }
 
-        assert!(
-            (result_float - expected_result).abs() < 0.1,
-            "Test case 1 failed: got {}, expected {}",
-            result_float,
-            expected_result
-        );
+    pub fn bit_not(self) -> Self {
+        // Flips every bit of the raw scaled integer, which equals
+        // `-x - 2^-exp` rather than `-x`
+        Fixed32::new(!self.value, self.exp)
     }
 
-    fn test_reciprocal(divisor: f32) {
-        let fixed = Fixed32::from(divisor, 24);
-        let reciprocal_fixed = fixed.reciprocal();
+    pub fn normalize(self) -> Self {
+        // Removes trailing zero bits from `value` by lowering `exp`, so that
+        // equal numbers share the same representation
+        if self.value == 0 {
+            return Fixed32::new(0, 0);
+        }
 
-        let result = reciprocal_fixed.to_f32();
-        let expected_result = 1. / divisor;
-        println!("result: {}", result);
-        println!("expected_result: {}", expected_result);
+        let shift = (self.value.trailing_zeros() as i32).min(self.exp).max(0);
+        Fixed32::new(self.value >> shift, self.exp - shift)
+    }
 
-        assert!(
-            diff(expected_result, result) < 0.1,
-            "test case failed: got {}, expected {}",
-            result,
-            expected_result
-        )
+    #[deprecated(
+        note = "truncates silently; use `to_fixed32_at_exp` with an explicit `RoundingMode`"
+    )]
+    pub fn normalize_to_exp(self, target_exp: i32) -> Self {
+        // Converts to `target_exp`; lowering the exponent truncates toward
+        // negative infinity
+        if target_exp >= self.exp {
+            Fixed32::new(self.value << (target_exp - self.exp), target_exp)
+        } else {
+            Fixed32::new(self.value >> (self.exp - target_exp), target_exp)
+        }
     }
 
-    #[test]
-    fn test_reciprocal_1() {
-        test_reciprocal(0.22)
+    pub fn to_fixed32_at_exp(
+        self,
+        target_exp: i32,
+        mode: RoundingMode,
+    ) -> Self {
+        // The primary API for changing precision. Raising the exponent is
+        // exact (unless the integer part overflows); lowering it rounds the
+        // discarded bits according to `mode`
+        self.rescale_with_rounding(target_exp, mode)
     }
 
-    #[test]
-    fn test_reciprocal_2() {
-        test_reciprocal(3.15)
+    fn rescale_with_rounding(
+        self,
+        target_exp: i32,
+        mode: RoundingMode,
+    ) -> Self {
+        let shift = self.exp - target_exp;
+        if shift <= 0 {
+            return Fixed32::new(self.value << -shift, target_exp);
+        }
+
+        // `floor + remainder / 2^shift` is the exact rescaled value, with the
+        // remainder in [0, 2^shift)
+        let value = self.value as i64;
+        let floor = value >> shift;
+        let remainder = value - (floor << shift);
+        let half = 1i64 << (shift - 1);
+        let round_up = match mode {
+            RoundingMode::Floor => false,
+            RoundingMode::Ceil => remainder != 0,
+            RoundingMode::TowardZero => value < 0 && remainder != 0,
+            RoundingMode::HalfAwayFromZero => {
+                remainder > half || (remainder == half && value >= 0)
+            }
+            RoundingMode::HalfToEven => {
+                remainder > half || (remainder == half && floor % 2 != 0)
+            }
+        };
+
+        Fixed32::new((floor + round_up as i64) as i32, target_exp)
     }
 
-    #[test]
-    fn test_reciprocal_3() {
-        test_reciprocal(107.4)
+    pub fn abs(self) -> Self {
+        Fixed32::new(self.value.abs(), self.exp)
     }
 
-    #[test]
-    fn test_reciprocal_4() {
-        test_reciprocal(0.008375)
+    pub fn signum_fixed(self) -> Fixed32 {
+        // Returns 1, -1 or 0 at the same exponent, so the result can be used
+        // directly in arithmetic with `self`
+        match self.value.signum() {
+            1 => Fixed32::new(1 << self.exp, self.exp),
+            -1 => Fixed32::new(-(1 << self.exp), self.exp),
+            _ => Fixed32::new(0, self.exp),
+        }
     }
 
-    #[test]
-    fn test_div_dividend_less_than_1() {
-        let a = 20.;
-        let b = 0.31;
-        let a_fixed = Fixed32::from(a, 24);
-        let b_fixed = Fixed32::from(b, 24);
-        let result = a_fixed / b_fixed;
+    pub fn negate_if(self, cond: bool) -> Fixed32 {
+        // Branch-free equivalent of `if cond { -self } else { self }`: the
+        // mask is all ones when `cond` holds, and `(v ^ -1) + 1 == -v`
+        let mask = -(cond as i32);
+        Fixed32::new((self.value ^ mask).wrapping_add(cond as i32), self.exp)
+    }
 
-        let result = result.to_f32();
-        let expected_result = a / b;
-        println!("{}", result);
-        println!("{}", expected_result);
+    pub fn approx_eq(self, other: Fixed32, tolerance: Fixed32) -> bool {
+        // Checks `|self - other| <= tolerance` after aligning all three values
+        // to the largest exponent, using 64 bits so the difference cannot
+        // overflow
+        let exp = self.exp.max(other.exp).max(tolerance.exp);
+        let difference = self.value_at_exp(exp) - other.value_at_exp(exp);
 
-        assert!(
-            diff(expected_result, result) < 0.1,
-            "test case failed: got {}, expected {}",
-            result,
-            expected_result
-        );
+        difference.abs() <= tolerance.value_at_exp(exp).abs()
     }
 
-    #[test]
-    fn test_div_not_divisible() {
-        let a = 20.;
-        let b = 6.;
-        let a_fixed = Fixed32::from(a, 5);
-        let b_fixed = Fixed32::from(b, 5);
-        let result = a_fixed / b_fixed;
-        println!("{}", result.value);
+    pub fn approx_eq_within_percent(self, other: Fixed32, pct: f32) -> bool {
+        // Relative tolerance `|self - other| / |self| < pct / 100`, taking
+        // `self` as the reference value. Fails for any `other` when `self` is
+        // zero, and is overly strict near zero; use `approx_eq` there
+        crate::measure::diff(self.to_f32(), other.to_f32()).abs() < pct / 100.
+    }
+
+    pub fn approx_eq_ulps(self, other: Fixed32, max_ulps: i64) -> bool {
+        // Like `approx_eq`, but the tolerance is a number of units in the last
+        // place at the larger of the two exponents
+        let exp = self.exp.max(other.exp);
+        let difference = self.value_at_exp(exp) - other.value_at_exp(exp);
+
+        difference.abs() <= max_ulps
+    }
+
+    pub fn div_exact(self, other: Fixed32) -> Option<Self> {
+        // Divides without Newton-Raphson; returns `None` unless the quotient
+        // is exactly representable at `exp`
+        if self.exp != other.exp {
+            panic!(
+                "Only support division between two fixed-point numbers with \
+            the same exponential!"
+            )
+        }
+
+        if other.value == 0 {
+            return None;
+        }
+
+        let dividend = (self.value as i64) << self.exp;
+        let divisor = other.value as i64;
+        if dividend % divisor != 0 {
+            return None;
+        }
+
+        i32::try_from(dividend / divisor)
+            .ok()
+            .map(|value| Fixed32::new(value, self.exp))
+    }
+
+    pub fn div_remainder(
+        self,
+        other: Fixed32,
+    ) -> Result<(Fixed32, Fixed32), ArithmeticError> {
+        // Truncating division of the scaled values: the quotient is an
+        // integer, the remainder has the sign of `self`, and
+        // `quotient * other + remainder == self` holds exactly
+        if self.exp != other.exp {
+            panic!(
+                "Only support division between two fixed-point numbers with \
+            the same exponential!"
+            )
+        }
+
+        if other.value == 0 {
+            return Err(ArithmeticError::DivisionByZero);
+        }
+
+        let quotient = self
+            .value
+            .checked_div(other.value)
+            .ok_or(ArithmeticError::Overflow)?;
+        let remainder = self.value - quotient * other.value;
+        let quotient = i32::try_from((quotient as i64) << self.exp)
+            .map_err(|_| ArithmeticError::Overflow)?;
+
+        Ok((
+            Fixed32::new(quotient, self.exp),
+            Fixed32::new(remainder, self.exp),
+        ))
+    }
+
+    pub fn iter_range(
+        start: Fixed32,
+        end: Fixed32,
+        step: Fixed32,
+    ) -> impl Iterator<Item = Fixed32> {
+        // Yields `start`, `start + step`, ... while the value is at most
+        // `end`. Stops early instead of overflowing near `i32::MAX`
+        if start.exp != end.exp || start.exp != step.exp {
+            panic!(
+                "Only support ranges between fixed-point numbers with the \
+            same exponential!"
+            )
+        }
+        if step.value <= 0 {
+            panic!("The step of a range must be positive!")
+        }
+
+        let first = Some(start).filter(|x| x.value <= end.value);
+        std::iter::successors(first, move |x| {
+            x.value
+                .checked_add(step.value)
+                .filter(|&value| value <= end.value)
+                .map(|value| Fixed32::new(value, x.exp))
+        })
+    }
+
+    pub fn midpoint(a: Fixed32, b: Fixed32) -> Fixed32 {
+        // (a + b) / 2 rounded toward negative infinity, without ever forming
+        // `a + b`: the shared bits count fully and the differing bits half.
+        // Even `a + (b - a) / 2` overflows when the signs differ
+        if a.exp != b.exp {
+            panic!(
+                "Only support midpoint between two fixed-point numbers with \
+            the same exponential!"
+            )
+        }
+
+        Fixed32::new((a.value & b.value) + ((a.value ^ b.value) >> 1), a.exp)
+    }
+
+    pub fn midpoint_i64(a: Fixed32, b: Fixed32) -> Fixed32 {
+        // Same result as `midpoint`, computed by widening to 64 bits
+        if a.exp != b.exp {
+            panic!(
+                "Only support midpoint between two fixed-point numbers with \
+            the same exponential!"
+            )
+        }
+
+        let sum = a.value as i64 + b.value as i64;
+        Fixed32::new((sum >> 1) as i32, a.exp)
+    }
+
+    pub fn overflowing_add(self, other: Fixed32) -> (Fixed32, bool) {
+        // Like `i32::overflowing_add`: the wrapped sum and whether it wrapped
+        if self.exp != other.exp {
+            panic!(
+                "Only support addition between two fixed-point numbers with \
+            the same exponential!"
+            )
+        }
+
+        let (sum, overflow) = self.value.overflowing_add(other.value);
+        (Fixed32::new(sum, self.exp), overflow)
+    }
+
+    pub fn overflowing_mul(self, other: Fixed32) -> (Fixed32, bool) {
+        // The full product is exact in 64 bits; it overflows when the
+        // rescaled result does not fit back into 32 bits, and then wraps like
+        // `Mul`
+        if self.exp != other.exp {
+            panic!(
+                "Only support multiplication between two fixed-point numbers \
+            with the same exponential!"
+            )
+        }
+
+        let product = (self.value as i64 * other.value as i64) >> self.exp;
+        let wrapped = product as i32;
+        (Fixed32::new(wrapped, self.exp), wrapped as i64 != product)
+    }
+
+    pub fn two_sum_exact(a: Fixed32, b: Fixed32) -> (Fixed32, i32) {
+        // Error-free transformation: returns the wrapped sum at the larger
+        // exponent and the number of times it wrapped. Aligned fixed-point
+        // additions never round, so the wrap count is the whole correction:
+        // in raw units of the sum, `a + b == sum.value() + wraps * 2^32`
+        // exactly. It is always -1, 0 or 1
+        let exp = a.exp.max(b.exp);
+        let exact = a.value_at_exp(exp) + b.value_at_exp(exp);
+        let sum = exact as i32;
+        let wraps = (exact - sum as i64) >> 32;
+
+        (Fixed32::new(sum, exp), wraps as i32)
+    }
+
+    pub fn double_precision_mul(a: Fixed32, b: Fixed32) -> (Fixed32, Fixed32) {
+        // Splits the exact 64-bit product into `hi`, the truncated product
+        // that `Mul` returns, and the discarded low bits `lo`, which hold
+        // `2 * exp` fractional bits and always lie in [0, 2^-exp). For
+        // `exp <= 15`, `hi + lo` equals `a * b` exactly; above that `lo` is
+        // outside the range `Add` supports, so use `combine_double_precision`
+        if a.exp != b.exp {
+            panic!(
+                "Only support multiplication between two fixed-point \
+            numbers with the same exponential!"
+            )
+        }
+
+        let product = a.value as i64 * b.value as i64;
+        let hi = product >> a.exp;
+        let lo = product - (hi << a.exp);
+        let hi = i32::try_from(hi)
+            .expect("The product does not fit in a fixed-point number!");
+
+        (Fixed32::new(hi, a.exp), Fixed32::new(lo as i32, a.exp * 2))
+    }
+
+    pub fn combine_double_precision(hi: Fixed32, lo: Fixed32) -> i64 {
+        // The exact product from `double_precision_mul`, as a raw value with
+        // `2 * exp` fractional bits
+        if lo.exp != hi.exp * 2 {
+            panic!(
+                "The low half must have twice the exponential of the high \
+            half!"
+            )
+        }
+
+        ((hi.value as i64) << hi.exp) + lo.value as i64
+    }
+
+    fn value_at_exp(self, exp: i32) -> i64 {
+        (self.value as i64) << (exp - self.exp)
+    }
+
+    pub fn to_i32_truncating(self) -> i32 {
+        // Discards the fractional bits, rounding toward zero
+        if self.value < 0 {
+            self.to_i32_ceil()
+        } else {
+            self.to_i32_floor()
+        }
+    }
+
+    pub fn to_i32_floor(self) -> i32 {
+        self.value >> self.exp
+    }
+
+    pub fn to_i32_ceil(self) -> i32 {
+        (-(-(self.value as i64) >> self.exp)) as i32
+    }
+
+    pub fn to_i32_round(self) -> i32 {
+        // Rounds to the nearest integer, with ties going to the even one
+        if self.exp == 0 {
+            return self.value;
+        }
+
+        let floor = (self.value >> self.exp) as i64;
+        let remainder = self.value as i64 - (floor << self.exp);
+        let half = 1i64 << (self.exp - 1);
+        if remainder > half || (remainder == half && floor % 2 != 0) {
+            (floor + 1) as i32
+        } else {
+            floor as i32
+        }
+    }
+
+    // Q formats name a 32-bit fixed-point number by its integer bits
+    // (including the sign bit) and fractional bits, so `exp = n` is Q(32-n).n:
+    //
+    //    0 = Q32.0     8 = Q24.8    16 = Q16.16   24 = Q8.24
+    //    1 = Q31.1     9 = Q23.9    17 = Q15.17   25 = Q7.25
+    //    2 = Q30.2    10 = Q22.10   18 = Q14.18   26 = Q6.26
+    //    3 = Q29.3    11 = Q21.11   19 = Q13.19   27 = Q5.27
+    //    4 = Q28.4    12 = Q20.12   20 = Q12.20   28 = Q4.28
+    //    5 = Q27.5    13 = Q19.13   21 = Q11.21   29 = Q3.29
+    //    6 = Q26.6    14 = Q18.14   22 = Q10.22   30 = Q2.30
+    //    7 = Q25.7    15 = Q17.15   23 = Q9.23    31 = Q1.31
+    //
+    // `from_q15`, `from_q16` and `from_q31` build the common ones directly.
+    pub fn from_q15(value: i16) -> Fixed32 {
+        // Q15 (Q1.15) as used by CMSIS-DSP: 15 fractional bits in an `i16`
+        Fixed32::new(value as i32, 15)
+    }
+
+    pub fn to_q15(self) -> i16 {
+        // Truncates extra fractional bits and saturates values outside the
+        // Q15 range [-1, 1 - 2^-15]
+        self.value_at_exp_saturating(15, i16::MIN as i64, i16::MAX as i64)
+            as i16
+    }
+
+    pub fn from_q16(value: i32) -> Fixed32 {
+        // Q16.16: 16 integer and 16 fractional bits
+        Fixed32::new(value, 16)
+    }
+
+    pub fn to_q16(self) -> i32 {
+        // Truncates extra fractional bits and saturates values outside
+        // [-32768, 32768 - 2^-16]
+        self.value_at_exp_saturating(16, i32::MIN as i64, i32::MAX as i64)
+            as i32
+    }
+
+    pub fn from_q31(value: i32) -> Fixed32 {
+        // Q31 (Q1.31): 31 fractional bits, covering [-1, 1 - 2^-31]
+        Fixed32::new(value, 31)
+    }
+
+    pub fn to_q31(self) -> i32 {
+        // Truncates extra fractional bits and saturates values outside the
+        // Q31 range
+        self.value_at_exp_saturating(31, i32::MIN as i64, i32::MAX as i64)
+            as i32
+    }
+
+    fn value_at_exp_saturating(self, exp: i32, min: i64, max: i64) -> i64 {
+        // Rescales to `exp`, truncating toward negative infinity, and clamps
+        // the result to [min, max]. Integer parts too large for 64 bits also
+        // saturate
+        let value = if self.exp >= exp {
+            (self.value >> (self.exp - exp)) as i64
+        } else {
+            let shifted = (self.value as i128) << (exp - self.exp);
+            shifted.clamp(i64::MIN as i128, i64::MAX as i128) as i64
+        };
+
+        value.clamp(min, max)
+    }
+
+    pub fn from_u8_array(bytes: &[u8], exp: i32) -> Vec<Fixed32> {
+        // Maps 8-bit pixel intensities [0, 255] onto [0, 1]
+        bytes
+            .iter()
+            .map(|&b| Fixed32::from(b as f32 / 255., exp))
+            .collect()
+    }
+
+    pub fn to_u8_array(values: &[Fixed32]) -> Vec<u8> {
+        // The inverse of `from_u8_array`; values outside [0, 1] are clamped
+        values
+            .iter()
+            .map(|value| (value.to_f32().clamp(0., 1.) * 255.).round() as u8)
+            .collect()
+    }
+
+    pub fn to_q_format_string(&self) -> String {
+        // Formats as "Qm.n: value", where m counts the integer bits including
+        // the sign bit. The value is exact because every 32-bit fixed-point
+        // number fits in an `f64`, and `f64` formatting round-trips
+        let value = self.value as f64 / (1u64 << self.exp) as f64;
+        format!("Q{}.{}: {}", 32 - self.exp, self.exp, value)
+    }
+
+    pub fn from_q_format_str(s: &str) -> Result<Self, ParseFixedError> {
+        // Parses strings produced by `to_q_format_string`; values with more
+        // fractional digits than the format holds are rounded
+        let s = s.trim();
+        if s.is_empty() {
+            return Err(ParseFixedError::Empty);
+        }
+        let (format, value) = s
+            .strip_prefix('Q')
+            .and_then(|rest| rest.split_once(':'))
+            .ok_or(ParseFixedError::InvalidFormat)?;
+        let (integer_bits, exp) = format
+            .split_once('.')
+            .and_then(|(m, n)| {
+                Some((m.parse::<i32>().ok()?, n.parse::<i32>().ok()?))
+            })
+            .ok_or(ParseFixedError::InvalidFormat)?;
+        if integer_bits < 1 || exp < 0 || integer_bits + exp != 32 {
+            return Err(ParseFixedError::InvalidFormat);
+        }
+
+        let value: f64 = value
+            .trim()
+            .parse()
+            .map_err(|_| ParseFixedError::InvalidFormat)?;
+        let scaled = (value * (1u64 << exp) as f64).round();
+        if scaled < i32::MIN as f64 || scaled > i32::MAX as f64 {
+            return Err(ParseFixedError::Overflow);
+        }
+
+        Ok(Fixed32::new(scaled as i32, exp))
+    }
+
+    fn split_magnitude(self) -> (&'static str, u64, u64) {
+        // Splits the absolute value into its integer and fractional bits
+        let sign = if self.value < 0 { "-" } else { "" };
+        let magnitude = self.value.unsigned_abs() as u64;
+        let fractional_mask = (1u64 << self.exp) - 1;
+
+        (sign, magnitude >> self.exp, magnitude & fractional_mask)
+    }
+}
+
+fn parse_binary_digit(c: char) -> Result<i64, ParseFixedError> {
+    match c {
+        '0' => Ok(0),
+        '1' => Ok(1),
+        _ => Err(ParseFixedError::InvalidDigit(c)),
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FixedAccumulator {
+    // The running sum, with 32 extra bits of headroom over `Fixed32`
+    sum: i64,
+
+    // The exponent shared by every accumulated value
+    exp: i32,
+
+    // The number of accumulated values
+    count: u64,
+}
+
+impl FixedAccumulator {
+    pub fn new(exp: i32) -> Self {
+        Self {
+            sum: 0,
+            exp,
+            count: 0,
+        }
+    }
+
+    pub fn exp(&self) -> i32 {
+        self.exp
+    }
+
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    pub fn add(&mut self, val: Fixed32) {
+        if val.exp != self.exp {
+            panic!(
+                "Only support accumulating fixed-point numbers with the same \
+            exponential!"
+            )
+        }
+
+        self.sum += val.value as i64;
+        self.count += 1;
+    }
+
+    pub fn finish(self) -> Option<Fixed32> {
+        // Intermediate sums may exceed 32 bits; only the final sum has to
+        // fit back into a `Fixed32`
+        let value = i32::try_from(self.sum).ok()?;
+        Some(Fixed32::new(value, self.exp))
+    }
+
+    pub fn sum_slice(values: &[Fixed32]) -> Fixed32 {
+        // Sums values sharing one exponent; an empty slice returns zero at
+        // `exp = 0`, like `Sum`
+        let exp = values.first().map_or(0, |x| x.exp);
+        let mut accumulator = FixedAccumulator::new(exp);
+        for &value in values {
+            accumulator.add(value);
+        }
+
+        accumulator
+            .finish()
+            .expect("The sum does not fit in a fixed-point number!")
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RoundingMode {
+    // Toward negative infinity, which is what a plain right shift does
+    Floor,
+
+    // Toward positive infinity
+    Ceil,
+
+    // Discards the fractional bits of the magnitude
+    TowardZero,
+
+    // To the nearest value, with ties going away from zero
+    HalfAwayFromZero,
+
+    // To the nearest value, with ties going to an even last bit
+    HalfToEven,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParseFixedError {
+    // The input string contains no digits
+    Empty,
+
+    // The input string contains a character that is not a valid digit
+    InvalidDigit(char),
+
+    // The parsed value does not fit into the 32-bit scaled integer
+    Overflow,
+
+    // The input string is not in the expected "Qm.n: value" layout
+    InvalidFormat,
+
+    // The radix is not one of 2, 8, 10 and 16
+    UnsupportedRadix(u32),
+}
+
+impl fmt::Display for ParseFixedError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseFixedError::Empty => {
+                write!(f, "cannot parse from empty string")
+            }
+            ParseFixedError::InvalidDigit(c) => {
+                write!(f, "invalid digit found in string: {:?}", c)
+            }
+            ParseFixedError::Overflow => {
+                write!(f, "number too large to fit in fixed-point value")
+            }
+            ParseFixedError::InvalidFormat => {
+                write!(f, "invalid Q format string")
+            }
+            ParseFixedError::UnsupportedRadix(radix) => {
+                write!(
+                    f,
+                    "unsupported radix {}, expected 2, 8, 10 or 16",
+                    radix
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for ParseFixedError {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConversionError {
+    // The value does not fit into the range of the target type
+    Overflow,
+
+    // The conversion would truncate non-zero fractional bits
+    PrecisionLoss,
+}
+
+impl fmt::Display for ConversionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConversionError::Overflow => {
+                write!(f, "value out of range for the target type")
+            }
+            ConversionError::PrecisionLoss => {
+                write!(f, "conversion would lose fractional precision")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ConversionError {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArithmeticError {
+    // The divisor is zero
+    DivisionByZero,
+
+    // The result does not fit into the 32-bit scaled integer
+    Overflow,
+}
+
+impl fmt::Display for ArithmeticError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ArithmeticError::DivisionByZero => write!(f, "division by zero"),
+            ArithmeticError::Overflow => {
+                write!(f, "result too large to fit in fixed-point value")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ArithmeticError {}
+
+#[cfg(feature = "fixed-crate")]
+impl From<::fixed::FixedI32<::fixed::types::extra::U24>> for Fixed32 {
+    fn from(value: ::fixed::FixedI32<::fixed::types::extra::U24>) -> Self {
+        Fixed32::new(value.to_bits(), 24)
+    }
+}
+
+#[cfg(feature = "fixed-crate")]
+impl TryFrom<Fixed32> for ::fixed::FixedI32<::fixed::types::extra::U24> {
+    type Error = ConversionError;
+
+    fn try_from(value: Fixed32) -> Result<Self, Self::Error> {
+        // Rescales the raw value to 24 fractional bits
+        let bits: i64 = if value.exp <= 24 {
+            (value.value as i64) << (24 - value.exp)
+        } else {
+            let shift = value.exp - 24;
+            if value.value & ((1 << shift) - 1) != 0 {
+                return Err(ConversionError::PrecisionLoss);
+            }
+            (value.value >> shift) as i64
+        };
+
+        match i32::try_from(bits) {
+            Ok(bits) => Ok(Self::from_bits(bits)),
+            Err(_) => Err(ConversionError::Overflow),
+        }
+    }
+}
+
+// Exponent used for values drawn from the `Standard` distribution
+#[cfg(feature = "rand")]
+const STANDARD_SAMPLE_EXP: i32 = 24;
+
+#[cfg(feature = "rand")]
+impl rand::distributions::Distribution<Fixed32>
+    for rand::distributions::Standard
+{
+    fn sample<R: rand::Rng + ?Sized>(&self, rng: &mut R) -> Fixed32 {
+        // Draws a value uniformly from [0, 1) with `STANDARD_SAMPLE_EXP`
+        // fractional bits
+        let value = rng.gen_range(0..1 << STANDARD_SAMPLE_EXP);
+        Fixed32::new(value, STANDARD_SAMPLE_EXP)
+    }
+}
+
+#[cfg(feature = "rand")]
+#[derive(Debug, Clone, Copy)]
+pub struct UniformFixed32 {
+    // Samples the raw scaled integer between the two bounds
+    inner: rand::distributions::Uniform<i32>,
+
+    // The exponent shared by both bounds and every sampled value
+    exp: i32,
+}
+
+#[cfg(feature = "rand")]
+impl rand::distributions::uniform::UniformSampler for UniformFixed32 {
+    type X = Fixed32;
+
+    fn new<B1, B2>(low: B1, high: B2) -> Self
+    where
+        B1: rand::distributions::uniform::SampleBorrow<Fixed32> + Sized,
+        B2: rand::distributions::uniform::SampleBorrow<Fixed32> + Sized,
+    {
+        let (low, high) = (*low.borrow(), *high.borrow());
+        UniformFixed32 {
+            inner: rand::distributions::Uniform::new(low.value, high.value),
+            exp: same_exp(low, high),
+        }
+    }
+
+    fn new_inclusive<B1, B2>(low: B1, high: B2) -> Self
+    where
+        B1: rand::distributions::uniform::SampleBorrow<Fixed32> + Sized,
+        B2: rand::distributions::uniform::SampleBorrow<Fixed32> + Sized,
+    {
+        let (low, high) = (*low.borrow(), *high.borrow());
+        UniformFixed32 {
+            inner: rand::distributions::Uniform::new_inclusive(
+                low.value, high.value,
+            ),
+            exp: same_exp(low, high),
+        }
+    }
+
+    fn sample<R: rand::Rng + ?Sized>(&self, rng: &mut R) -> Fixed32 {
+        use rand::distributions::Distribution;
+
+        Fixed32::new(self.inner.sample(rng), self.exp)
+    }
+}
+
+#[cfg(feature = "rand")]
+impl rand::distributions::uniform::SampleUniform for Fixed32 {
+    type Sampler = UniformFixed32;
+}
+
+#[cfg(feature = "rand")]
+fn same_exp(low: Fixed32, high: Fixed32) -> i32 {
+    if low.exp != high.exp {
+        panic!(
+            "Only support uniform sampling between two fixed-point numbers \
+            with the same exponential!"
+        )
+    }
+
+    low.exp
+}
+
+// `Fixed32` is `#[repr(C)]` with two `i32` fields and no padding, so it can be
+// viewed as raw memory: `value` comes first and `exp` second. A slice of N
+// numbers casts to 2N `i32`s laid out as [value, exp, value, exp, ...]
+#[cfg(feature = "bytemuck")]
+unsafe impl bytemuck::Zeroable for Fixed32 {}
+
+#[cfg(feature = "bytemuck")]
+unsafe impl bytemuck::Pod for Fixed32 {}
+
+impl Add for Fixed32 {
+    type Output = Fixed32;
+
+    fn add(self, other: Self) -> Self::Output {
+        debug_assert!(
+            valid_exp(self.exp) && valid_exp(other.exp),
+            "The exponential must be between 0 and 30!"
+        );
+        if self.exp == other.exp {
+            Fixed32::new(self.value + other.value, self.exp)
+        } else if self.exp > other.exp {
+            let shift = self.exp - other.exp;
+            Fixed32::new(self.value + (other.value << shift), self.exp)
+        } else {
+            let shift = other.exp - self.exp;
+            Fixed32::new((self.value << shift) + other.value, other.exp)
+        }
+    }
+}
+
+impl Sub for Fixed32 {
+    type Output = Fixed32;
+
+    fn sub(self, other: Self) -> Self::Output {
+        debug_assert!(
+            valid_exp(self.exp) && valid_exp(other.exp),
+            "The exponential must be between 0 and 30!"
+        );
+        if self.exp == other.exp {
+            Fixed32::new(self.value - other.value, self.exp)
+        } else if self.exp > other.exp {
+            let shift = self.exp - other.exp;
+            Fixed32::new(self.value - (other.value << shift), self.exp)
+        } else {
+            let shift = other.exp - self.exp;
+            Fixed32::new((self.value << shift) - other.value, other.exp)
+        }
+    }
+}
+
+impl Mul for Fixed32 {
+    type Output = Fixed32;
+
+    fn mul(self, other: Self) -> Self::Output {
+        if self.exp != other.exp {
+            panic!(
+                "Only support multiplication between two fixed-point \
+            numbers with the same exponential!"
+            )
+        }
+        debug_assert!(
+            valid_exp(self.exp),
+            "The exponential must be between 0 and 30!"
+        );
+
+        let val1: i64 = self.value as i64;
+        let val2: i64 = other.value as i64;
+        let product: i64 = (val1 * val2) >> self.exp;
+
+        Fixed32 {
+            value: product as i32,
+            exp: self.exp,
+        }
+    }
+}
+
+impl Div for Fixed32 {
+    type Output = Fixed32;
+
+    fn div(self, other: Self) -> Self::Output {
+        if self.exp != other.exp {
+            panic!(
+                "Only support multiplication between two fixed-point \
+            numbers with the same exponential!"
+            )
+        }
+        debug_assert!(
+            valid_exp(self.exp),
+            "The exponential must be between 0 and 30!"
+        );
+
+        if other.value == 0 {
+            panic!("Division by zero error!");
+        }
+
+        // Not accurate
+        // let quotient = self.value / other.value * (1 << self.exp);
+        // Self::new(quotient, self.exp)
+
+        // Not accurate when `other` is greater than 1
+        self * other.reciprocal()
+    }
+}
+
+impl Add<f32> for Fixed32 {
+    type Output = Fixed32;
+
+    fn add(self, scalar: f32) -> Self::Output {
+        // Converts the scalar to `self.exp` first
+        self + Fixed32::from(scalar, self.exp)
+    }
+}
+
+impl Sub<f32> for Fixed32 {
+    type Output = Fixed32;
+
+    fn sub(self, scalar: f32) -> Self::Output {
+        self - Fixed32::from(scalar, self.exp)
+    }
+}
+
+impl Mul<f32> for Fixed32 {
+    type Output = Fixed32;
+
+    fn mul(self, scalar: f32) -> Self::Output {
+        self * Fixed32::from(scalar, self.exp)
+    }
+}
+
+impl Div<f32> for Fixed32 {
+    type Output = Fixed32;
+
+    fn div(self, scalar: f32) -> Self::Output {
+        self / Fixed32::from(scalar, self.exp)
+    }
+}
+
+impl Add<Fixed32> for f32 {
+    type Output = Fixed32;
+
+    fn add(self, fixed: Fixed32) -> Self::Output {
+        // Converts the scalar to `fixed.exp` first
+        Fixed32::from(self, fixed.exp) + fixed
+    }
+}
+
+impl Sub<Fixed32> for f32 {
+    type Output = Fixed32;
+
+    fn sub(self, fixed: Fixed32) -> Self::Output {
+        Fixed32::from(self, fixed.exp) - fixed
+    }
+}
+
+impl Mul<Fixed32> for f32 {
+    type Output = Fixed32;
+
+    fn mul(self, fixed: Fixed32) -> Self::Output {
+        Fixed32::from(self, fixed.exp) * fixed
+    }
+}
+
+impl Div<Fixed32> for f32 {
+    type Output = Fixed32;
+
+    fn div(self, fixed: Fixed32) -> Self::Output {
+        Fixed32::from(self, fixed.exp) / fixed
+    }
+}
+
+impl BitAnd<i32> for Fixed32 {
+    type Output = Fixed32;
+
+    fn bitand(self, mask: i32) -> Self::Output {
+        self.bit_and(mask)
+    }
+}
+
+impl BitOr<i32> for Fixed32 {
+    type Output = Fixed32;
+
+    fn bitor(self, mask: i32) -> Self::Output {
+        self.bit_or(mask)
+    }
+}
+
+impl BitXor<i32> for Fixed32 {
+    type Output = Fixed32;
+
+    fn bitxor(self, mask: i32) -> Self::Output {
+        self.bit_xor(mask)
+    }
+}
+
+impl Neg for Fixed32 {
+    type Output = Fixed32;
+
+    fn neg(self) -> Self::Output {
+        Fixed32::new(-self.value, self.exp)
+    }
+}
+
+impl Not for Fixed32 {
+    type Output = Fixed32;
+
+    fn not(self) -> Self::Output {
+        self.bit_not()
+    }
+}
+
+impl Sum for Fixed32 {
+    fn sum<I: Iterator<Item = Fixed32>>(iter: I) -> Self {
+        // Fixed-point additions are exact, so there is no rounding error to
+        // compensate; accumulating in 64 bits instead lets intermediate sums
+        // overflow as long as the total fits. Like `Add`, the result takes
+        // the largest exponent. An empty iterator returns zero at `exp = 0`,
+        // which `Add` treats as an identity for any exponent
+        let mut sum: i64 = 0;
+        let mut exp = 0;
+        for x in iter {
+            if x.exp > exp {
+                sum <<= x.exp - exp;
+                exp = x.exp;
+            }
+            sum += (x.value as i64) << (exp - x.exp);
+        }
+
+        let value = i32::try_from(sum)
+            .expect("The sum does not fit in a fixed-point number!");
+        Fixed32::new(value, exp)
+    }
+}
+
+impl<'a> Sum<&'a Fixed32> for Fixed32 {
+    fn sum<I: Iterator<Item = &'a Fixed32>>(iter: I) -> Self {
+        iter.copied().sum()
+    }
+}
+
+impl Product for Fixed32 {
+    fn product<I: Iterator<Item = Fixed32>>(iter: I) -> Self {
+        // `Mul` requires matching exponents, so the first factor sets the
+        // exponent; an empty iterator returns one at `exp = 0`
+        iter.reduce(|a, b| a * b).unwrap_or(Fixed32::new(1, 0))
+    }
+}
+
+impl<'a> Product<&'a Fixed32> for Fixed32 {
+    fn product<I: Iterator<Item = &'a Fixed32>>(iter: I) -> Self {
+        iter.copied().product()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::measure::relative_error_percent;
+
+    #[test]
+    fn test_add_same_exp() {
+        let a = Fixed32::new(10, 4);
+        let b = Fixed32::new(15, 4);
+        let result = a + b;
+        assert_eq!(result.value, 25);
+        assert_eq!(result.exp, 4);
+    }
+
+    #[test]
+    fn test_add_different_exp() {
+        let a = Fixed32::new(10, 3);
+        let b = Fixed32::new(15, 2);
+        let result = a + b;
+        assert_eq!(result.value, 40);
+        assert_eq!(result.exp, 3);
+    }
+
+    #[test]
+    fn test_sub_same_exp() {
+        let a = Fixed32::new(20, 4);
+        let b = Fixed32::new(10, 4);
+        let result = a - b;
+        assert_eq!(result.value, 10);
+        assert_eq!(result.exp, 4);
+    }
+
+    #[test]
+    fn test_sub_different_exp() {
+        let a = Fixed32::new(40, 5);
+        let b = Fixed32::new(15, 3);
+        let result = a - b;
+        assert_eq!(result.value, -20);
+        assert_eq!(result.exp, 5);
+    }
+
+    #[test]
+    fn test_mul_same_exp() {
+        // (10 * 20) >> 4 = 200 >> 4 = 12
+        let a = Fixed32::from(2.47, 24);
+        let b = Fixed32::from(3.19, 24);
+        let result = a * b;
+        assert_eq!(result.to_f32(), 7.8793);
+        assert_eq!(result.exp, 24);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_mul_different_exp() {
+        let a = Fixed32::new(10, 4);
+        let b = Fixed32::new(20, 3);
+        let _result = a * b;
+    }
+
+    #[test]
+    fn test_div_divisible() {
+        let a = 20.;
+        let b = 5.;
+        let a_fixed = Fixed32::from(a, 5);
+        let b_fixed = Fixed32::from(b, 5);
+        let result = a_fixed / b_fixed;
+        println!("{}", result.value);
+
+        let result_float = result.to_f32();
+        let expected_result = a / b;
+
+        assert!(
+            (result_float - expected_result).abs() < 0.1,
+            "Test case 1 failed: got {}, expected {}",
+            result_float,
+            expected_result
+        );
+    }
+
+    fn test_reciprocal(divisor: f32) {
+        let fixed = Fixed32::from(divisor, 24);
+        let reciprocal_fixed = fixed.reciprocal();
+
+        let result = reciprocal_fixed.to_f32();
+        let expected_result = 1. / divisor;
+        println!("result: {}", result);
+        println!("expected_result: {}", expected_result);
+
+        // 2^14 ULPs is about 0.001 at exponent 24
+        let expected_fixed = Fixed32::from(expected_result, 24);
+        assert!(
+            reciprocal_fixed.approx_eq_ulps(expected_fixed, 1 << 14),
+            "test case failed: got {}, expected {} ({:.3}% error)",
+            result,
+            expected_result,
+            relative_error_percent(expected_result, result)
+        )
+    }
+
+    #[test]
+    fn test_reciprocal_1() {
+        test_reciprocal(0.22)
+    }
+
+    #[test]
+    fn test_reciprocal_2() {
+        test_reciprocal(3.15)
+    }
+
+    #[test]
+    fn test_reciprocal_3() {
+        test_reciprocal(107.4)
+    }
+
+    #[test]
+    fn test_reciprocal_4() {
+        test_reciprocal(0.008375)
+    }
+
+    #[test]
+    fn test_reciprocal_negative() {
+        test_reciprocal(-3.15);
+        test_reciprocal(-0.008375);
+    }
+
+    #[test]
+    fn test_div_dividend_less_than_1() {
+        let a = 20.;
+        let b = 0.31;
+        let a_fixed = Fixed32::from(a, 24);
+        let b_fixed = Fixed32::from(b, 24);
+        let result_fixed = a_fixed / b_fixed;
+
+        let result = result_fixed.to_f32();
+        let expected_result = a / b;
+        println!("{}", result);
+        println!("{}", expected_result);
+
+        assert!(
+            result_fixed
+                .approx_eq_ulps(Fixed32::from(expected_result, 24), 1000),
+            "test case failed: got {}, expected {} ({:.3}% error)",
+            result,
+            expected_result,
+            relative_error_percent(expected_result, result)
+        );
+    }
+
+    #[test]
+    fn test_div_not_divisible() {
+        let a = 20.;
+        let b = 6.;
+        let a_fixed = Fixed32::from(a, 5);
+        let b_fixed = Fixed32::from(b, 5);
+        let result = a_fixed / b_fixed;
+        println!("{}", result.value);
+
+        let result_float = result.to_f32();
+        let expected_result = a / b;
+
+        assert!(
+            (result_float - expected_result).abs() < 0.1,
+            "test case 1 failed: got {}, expected {}",
+            result_float,
+            expected_result
+        );
+    }
+
+    #[test]
+    fn test_from_binary_str() {
+        let result = Fixed32::from_binary_str("11.01", 4).unwrap();
+        assert_eq!(result.value, 0b1101 << 2);
+        assert_eq!(result.to_f32(), 3.25);
+
+        let result = Fixed32::from_binary_str("0.1", 24).unwrap();
+        assert_eq!(result.to_f32(), 0.5);
+
+        let result = Fixed32::from_binary_str("-10.1", 8).unwrap();
+        assert_eq!(result.to_f32(), -2.5);
+    }
+
+    #[test]
+    fn test_from_binary_str_truncates_extra_fraction_digits() {
+        let result = Fixed32::from_binary_str("0.0111", 2).unwrap();
+        assert_eq!(result, Fixed32::new(1, 2));
+    }
+
+    #[test]
+    fn test_from_binary_str_errors() {
+        assert_eq!(
+            Fixed32::from_binary_str("", 4),
+            Err(ParseFixedError::Empty)
+        );
+        assert_eq!(
+            Fixed32::from_binary_str("10.21", 4),
+            Err(ParseFixedError::InvalidDigit('2'))
+        );
+        assert_eq!(
+            Fixed32::from_binary_str("1x", 4),
+            Err(ParseFixedError::InvalidDigit('x'))
+        );
+        assert_eq!(
+            Fixed32::from_binary_str("100000000", 24),
+            Err(ParseFixedError::Overflow)
+        );
+    }
+
+    #[test]
+    fn test_from_decimal_string_lossless() {
+        let parse = |s| Fixed32::from_decimal_string_lossless(s, 16);
+        assert_eq!(parse("1.5"), Ok(Fixed32::from(1.5, 16)));
+        assert_eq!(parse("-12.375"), Ok(Fixed32::from(-12.375, 16)));
+        assert_eq!(parse(".25"), Ok(Fixed32::from(0.25, 16)));
+        assert_eq!(parse("7."), Ok(Fixed32::from(7., 16)));
+        assert_eq!(parse("-32768"), Ok(Fixed32::new(i32::MIN, 16)));
+        // Rounds to the nearest ULP, including into the integer part
+        assert_eq!(parse("0.99999999"), Ok(Fixed32::from(1., 16)));
+        assert_eq!(
+            parse("0.1234567890123456789012345"),
+            Ok(Fixed32::new(8091, 16))
+        );
+
+        assert_eq!(parse(""), Err(ParseFixedError::Empty));
+        assert_eq!(parse("-."), Err(ParseFixedError::Empty));
+        assert_eq!(parse("1.2e3"), Err(ParseFixedError::InvalidDigit('e')));
+        assert_eq!(parse("32768"), Err(ParseFixedError::Overflow));
+        assert_eq!(parse("99999999999"), Err(ParseFixedError::Overflow));
+    }
+
+    #[test]
+    fn test_from_decimal_string_more_accurate_than_f32() {
+        // 0.1 * 2^30 = 107374182.4 exactly
+        let exact = 0.1 * 2f64.powi(30);
+        let lossless =
+            Fixed32::from_decimal_string_lossless("0.1", 30).unwrap();
+        let via_f32 = Fixed32::from(0.1f32, 30);
+        assert_eq!(lossless.value(), 107374182);
+        assert!(
+            (lossless.value() as f64 - exact).abs()
+                < (via_f32.value() as f64 - exact).abs()
+        );
+    }
+
+    #[test]
+    fn test_from_str_radix() {
+        // Q16.16 and Q2.30 register values
+        let parse = Fixed32::from_str_radix;
+        assert_eq!(parse("0x00018000", 16, 16), Ok(Fixed32::from(1.5, 16)));
+        assert_eq!(parse("0X40000000", 16, 30), Ok(Fixed32::new(1 << 30, 30)));
+        assert_eq!(parse("0xC0000000", 16, 30), Ok(Fixed32::new(-1 << 30, 30)));
+        assert_eq!(parse("FFFF8000", 16, 16), Ok(Fixed32::from(-0.5, 16)));
+        assert_eq!(parse("-0x8000", 16, 16), Ok(Fixed32::from(-0.5, 16)));
+        assert_eq!(parse("0x7FFF", 16, 15).unwrap().value(), i16::MAX as i32);
+
+        assert_eq!(parse("0b1010", 2, 2), Ok(Fixed32::from(2.5, 2)));
+        assert_eq!(parse("0o17", 8, 0), Ok(Fixed32::new(15, 0)));
+        assert_eq!(parse("-40", 10, 4), Ok(Fixed32::from(-2.5, 4)));
+        assert_eq!(parse("-2147483648", 10, 0), Ok(Fixed32::new(i32::MIN, 0)));
+
+        assert_eq!(parse("0x", 16, 16), Err(ParseFixedError::Empty));
+        assert_eq!(
+            parse("0b12", 2, 0),
+            Err(ParseFixedError::InvalidDigit('2'))
+        );
+        assert_eq!(parse("0x1FFFFFFFF", 16, 0), Err(ParseFixedError::Overflow));
+        assert_eq!(parse("4294967295", 10, 0), Err(ParseFixedError::Overflow));
+        assert_eq!(
+            parse("0x10", 36, 0),
+            Err(ParseFixedError::UnsupportedRadix(36))
+        );
+        assert_eq!(
+            ParseFixedError::UnsupportedRadix(3).to_string(),
+            "unsupported radix 3, expected 2, 8, 10 or 16"
+        );
+    }
+
+    #[test]
+    fn test_to_binary_str() {
+        assert_eq!(Fixed32::from(3.25, 4).to_binary_str(), "11.0100");
+        assert_eq!(Fixed32::from(-2.5, 2).to_binary_str(), "-10.10");
+        assert_eq!(Fixed32::new(5, 0).to_binary_str(), "101");
+    }
+
+    #[test]
+    fn test_to_hex_str() {
+        assert_eq!(Fixed32::from(0.5, 24).to_hex_str(), "0.800000");
+        assert_eq!(Fixed32::from(-26.75, 6).to_hex_str(), "-1a.c0");
+    }
+
+    #[test]
+    fn test_binary_str_round_trip() {
+        for x in [
+            Fixed32::from(3.25, 4),
+            Fixed32::from(-107.4, 24),
+            Fixed32::from(0.008375, 24),
+            Fixed32::new(i32::MAX, 16),
+            Fixed32::new(i32::MIN, 16),
+        ] {
+            let binary = x.to_binary_str();
+            assert_eq!(Fixed32::from_binary_str(&binary, x.exp), Ok(x));
+        }
+    }
+
+    #[test]
+    fn test_shift_left() {
+        let x = Fixed32::from(1.5, 16);
+        assert_eq!(x.shift_left(2), Some(Fixed32::from(6., 16)));
+        assert_eq!(x.shift_left(15), None);
+        assert_eq!(
+            Fixed32::new(-1, 0).shift_left(31),
+            Some(Fixed32::new(i32::MIN, 0))
+        );
+        assert_eq!(
+            Fixed32::new(0, 16).shift_left(40),
+            Some(Fixed32::new(0, 16))
+        );
+    }
+
+    #[test]
+    fn test_shift_right_floors() {
+        assert_eq!(Fixed32::new(7, 0).shift_right(1), Fixed32::new(3, 0));
+        assert_eq!(Fixed32::new(-7, 0).shift_right(1), Fixed32::new(-4, 0));
+        assert_eq!(Fixed32::new(-7, 0).shift_right(40), Fixed32::new(-1, 0));
+    }
+
+    #[test]
+    fn test_shift_round_trip() {
+        for x in [
+            Fixed32::from(3.15, 24),
+            Fixed32::from(-0.22, 24),
+            Fixed32::new(12345, 8),
+        ] {
+            for n in 0..4 {
+                assert_eq!(x.shift_left(n).unwrap().shift_right(n), x);
+            }
+        }
+    }
+
+    #[cfg(feature = "bytemuck")]
+    #[test]
+    fn test_bytemuck_cast_slice() {
+        let values = [
+            Fixed32::from(1.5, 16),
+            Fixed32::from(-0.25, 8),
+            Fixed32::new(i32::MIN, 0),
+        ];
+        let raw: &[i32] = bytemuck::cast_slice(&values);
+
+        assert_eq!(raw.len(), 2 * values.len());
+        for (pair, value) in raw.chunks(2).zip(&values) {
+            assert_eq!(pair, [value.value(), value.exp()]);
+        }
+        assert_eq!(bytemuck::cast_slice::<i32, Fixed32>(raw), values);
+        assert_eq!(
+            <Fixed32 as bytemuck::Zeroable>::zeroed(),
+            Fixed32::new(0, 0)
+        );
+    }
+
+    #[cfg(feature = "fixed-crate")]
+    #[test]
+    fn test_fixed_crate_round_trip() {
+        use ::fixed::{
+            types::extra::U24,
+            FixedI32,
+        };
+
+        let original = FixedI32::<U24>::from_num(3.15);
+        let converted: Fixed32 = original.into();
+        assert_eq!(converted.to_f32(), original.to_num::<f32>());
+        assert_eq!(FixedI32::<U24>::try_from(converted), Ok(original));
+
+        let coarse = Fixed32::from(-2.5, 4);
+        assert_eq!(
+            FixedI32::<U24>::try_from(coarse),
+            Ok(FixedI32::<U24>::from_num(-2.5))
+        );
+        assert_eq!(
+            FixedI32::<U24>::try_from(Fixed32::new(1 << 30, 16)),
+            Err(ConversionError::Overflow)
+        );
+        assert_eq!(
+            FixedI32::<U24>::try_from(Fixed32::new(1, 28)),
+            Err(ConversionError::PrecisionLoss)
+        );
+    }
+
+    #[cfg(feature = "rand")]
+    #[test]
+    fn test_uniform_sample_mean() {
+        use rand::{
+            distributions::{
+                Distribution,
+                Uniform,
+            },
+            rngs::StdRng,
+            SeedableRng,
+        };
+
+        let mut rng = StdRng::seed_from_u64(42);
+        let uniform = Uniform::new_inclusive(
+            Fixed32::from(0., 24),
+            Fixed32::from(1., 24),
+        );
+        let samples: Vec<Fixed32> =
+            (0..1000).map(|_| uniform.sample(&mut rng)).collect();
+
+        assert!(samples.iter().all(|x| x.exp == 24));
+        assert!(samples.iter().all(|x| (0. ..=1.).contains(&x.to_f32())));
+        let mean = samples.iter().map(|x| x.to_f32()).sum::<f32>() / 1000.;
+        assert!((mean - 0.5).abs() < 0.05, "mean was {}", mean);
+    }
+
+    #[cfg(feature = "rand")]
+    #[test]
+    fn test_standard_sample_range() {
+        use rand::{
+            rngs::StdRng,
+            Rng,
+            SeedableRng,
+        };
+
+        let mut rng = StdRng::seed_from_u64(7);
+        for _ in 0..100 {
+            let x: Fixed32 = rng.gen();
+            assert!((0. ..1.).contains(&x.to_f32()));
+        }
+    }
+
+    #[test]
+    fn test_scale_by_integer() {
+        let x = Fixed32::from(1.25, 24);
+        assert_eq!(x.scale_by_integer(3).to_f32(), 3.75);
+        assert_eq!(x.scale_by_integer(-2).to_f32(), -2.5);
+        assert_eq!(x.scale_by_integer(0).to_f32(), 0.);
+        assert_eq!(x.scale_by_integer(2), x + x);
+    }
+
+    #[test]
+    fn test_scale_by_integer_saturates() {
+        let x = Fixed32::from(100., 24);
+        assert_eq!(x.scale_by_integer(2), Fixed32::new(i32::MAX, 24));
+        assert_eq!(x.scale_by_integer(-2), Fixed32::new(i32::MIN, 24));
+    }
+
+    #[test]
+    fn test_bitwise_methods() {
+        let x = Fixed32::new(0b1011_0110, 4);
+        assert_eq!(x.bit_and(0b1111_0000), Fixed32::new(0b1011_0000, 4));
+        assert_eq!(x.bit_or(0b0000_1001), Fixed32::new(0b1011_1111, 4));
+        assert_eq!(x.bit_xor(0b1111_1111), Fixed32::new(0b0100_1001, 4));
+        assert_eq!(x.bit_not(), Fixed32::new(!0b1011_0110, 4));
+    }
+
+    #[test]
+    fn test_bitwise_operators() {
+        let x = Fixed32::from(-2.75, 8);
+        assert_eq!(x & 0x0f, x.bit_and(0x0f));
+        assert_eq!(x | 0x0f, x.bit_or(0x0f));
+        assert_eq!(x ^ 0x0f, x.bit_xor(0x0f));
+        assert_eq!(!x, x.bit_not());
+    }
+
+    #[test]
+    fn test_bit_and_masks_fractional_bits() {
+        let x = Fixed32::from(3.75, 8);
+        let integer_mask = !((1 << 8) - 1);
+        assert_eq!((x & integer_mask).to_f32(), 3.);
+    }
+
+    #[test]
+    fn test_normalize() {
+        assert_eq!(Fixed32::new(8, 5).normalize(), Fixed32::new(1, 2));
+        assert_eq!(Fixed32::new(4, 4).normalize(), Fixed32::new(1, 2));
+        assert_eq!(Fixed32::new(12, 1).normalize(), Fixed32::new(6, 0));
+        assert_eq!(Fixed32::new(-6, 3).normalize(), Fixed32::new(-3, 2));
+        assert_eq!(Fixed32::new(7, 3).normalize(), Fixed32::new(7, 3));
+        assert_eq!(Fixed32::new(0, 24).normalize(), Fixed32::new(0, 0));
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn test_normalize_to_exp() {
+        let x = Fixed32::from(2.75, 2);
+        assert_eq!(x.normalize_to_exp(8), Fixed32::from(2.75, 8));
+        assert_eq!(x.normalize_to_exp(1), Fixed32::from(2.5, 1));
+        assert_eq!(Fixed32::from(-2.75, 2).normalize_to_exp(0).to_f32(), -3.);
+    }
+
+    #[test]
+    fn test_normalize_round_trip() {
+        for x in [
+            Fixed32::from(3.15, 24),
+            Fixed32::from(-0.5, 24),
+            Fixed32::from(6., 16),
+            Fixed32::new(1, 30),
+        ] {
+            let normalized = x.normalize();
+            assert_eq!(normalized.to_f32(), x.to_f32());
+            assert_eq!(
+                normalized.to_fixed32_at_exp(x.exp, RoundingMode::Floor),
+                x
+            );
+        }
+    }
+
+    #[test]
+    fn test_abs() {
+        assert_eq!(Fixed32::from(-2.5, 8).abs(), Fixed32::from(2.5, 8));
+        assert_eq!(Fixed32::from(2.5, 8).abs(), Fixed32::from(2.5, 8));
+    }
+
+    #[test]
+    fn test_approx_eq() {
+        let a = Fixed32::from(1., 16);
+        let tolerance = Fixed32::from(0.25, 16);
+
+        // Exactly at the tolerance
+        assert!(a.approx_eq(Fixed32::from(1.25, 16), tolerance));
+        assert!(a.approx_eq(Fixed32::from(0.75, 16), tolerance));
+
+        // Inside the tolerance
+        assert!(a.approx_eq(Fixed32::from(1.1, 16), tolerance));
+        assert!(a.approx_eq(a, Fixed32::new(0, 16)));
+
+        // Outside the tolerance
+        assert!(!a.approx_eq(
+            Fixed32::from(1.25, 16) + Fixed32::new(1, 16),
+            tolerance
+        ));
+        assert!(!a.approx_eq(Fixed32::from(-1., 16), tolerance));
+    }
+
+    #[test]
+    fn test_approx_eq_mixed_exp_and_extremes() {
+        let tolerance = Fixed32::from(0.5, 1);
+        assert!(
+            Fixed32::from(3., 24).approx_eq(Fixed32::from(3.5, 4), tolerance)
+        );
+        assert!(!Fixed32::new(i32::MAX, 0)
+            .approx_eq(Fixed32::new(i32::MIN, 0), tolerance));
+    }
+
+    #[test]
+    fn test_approx_eq_within_percent() {
+        let a = Fixed32::from(10., 16);
+        assert!(a.approx_eq_within_percent(Fixed32::from(10.9, 16), 10.));
+        assert!(!a.approx_eq_within_percent(Fixed32::from(11.1, 16), 10.));
+        assert!(Fixed32::from(-10., 16)
+            .approx_eq_within_percent(Fixed32::from(-9.5, 24), 10.));
+        assert!(!Fixed32::from(-10., 16)
+            .approx_eq_within_percent(Fixed32::from(10., 16), 10.));
+
+        // Nothing is within a percentage of zero
+        let zero = Fixed32::new(0, 16);
+        assert!(!zero.approx_eq_within_percent(zero, 10.));
+    }
+
+    #[test]
+    fn test_approx_eq_ulps() {
+        let a = Fixed32::new(1000, 16);
+        assert!(a.approx_eq_ulps(Fixed32::new(1003, 16), 3));
+        assert!(a.approx_eq_ulps(Fixed32::new(997, 16), 3));
+        assert!(!a.approx_eq_ulps(Fixed32::new(1004, 16), 3));
+        assert!(a.approx_eq_ulps(a, 0));
+
+        // Counted at the larger exponent
+        assert!(Fixed32::new(1, 4).approx_eq_ulps(Fixed32::new(17, 8), 1));
+        assert!(!Fixed32::new(1, 4).approx_eq_ulps(Fixed32::new(18, 8), 1));
+    }
+
+    #[test]
+    fn test_to_i32_rounding_modes() {
+        // (input, truncating, floor, ceil, round)
+        let cases = [
+            (0.5, 0, 0, 1, 0),
+            (1.5, 1, 1, 2, 2),
+            (2.5, 2, 2, 3, 2),
+            (2.75, 2, 2, 3, 3),
+            (-0.5, 0, -1, 0, 0),
+            (-1.5, -1, -2, -1, -2),
+            (-2.25, -2, -3, -2, -2),
+            (3., 3, 3, 3, 3),
+        ];
+        for (input, truncating, floor, ceil, round) in cases {
+            let x = Fixed32::from(input, 16);
+            assert_eq!(x.to_i32_truncating(), truncating, "trunc({})", input);
+            assert_eq!(x.to_i32_floor(), floor, "floor({})", input);
+            assert_eq!(x.to_i32_ceil(), ceil, "ceil({})", input);
+            assert_eq!(x.to_i32_round(), round, "round({})", input);
+        }
+    }
 
-        let result_float = result.to_f32();
-        let expected_result = a / b;
+    #[test]
+    fn test_to_i32_high_exp() {
+        // One ULP above 0.5 is lost when going through `f32` at exp = 30
+        let x = Fixed32::new((1 << 29) + 1, 30);
+        assert_eq!(x.to_i32_round(), 1);
+        assert_eq!(x.to_i32_truncating(), 0);
+        assert_eq!(Fixed32::new(7, 0).to_i32_round(), 7);
+    }
+
+    #[test]
+    fn test_div_exact() {
+        let x = Fixed32::from(3., 16);
+        assert_eq!(
+            x.div_exact(Fixed32::from(2., 16)),
+            Some(Fixed32::from(1.5, 16))
+        );
+        assert_eq!(
+            x.div_exact(Fixed32::from(8., 16)),
+            Some(Fixed32::from(0.375, 16))
+        );
+        assert_eq!(
+            x.div_exact(Fixed32::from(-0.5, 16)),
+            Some(Fixed32::from(-6., 16))
+        );
+        assert_eq!(
+            x.div_exact(Fixed32::from(3., 16)),
+            Some(Fixed32::from(1., 16))
+        );
+    }
+
+    #[test]
+    fn test_div_exact_inexact_or_invalid() {
+        let x = Fixed32::from(1., 16);
+        assert_eq!(x.div_exact(Fixed32::from(3., 16)), None);
+        assert_eq!(x.div_exact(Fixed32::new(0, 16)), None);
+        assert_eq!(
+            Fixed32::from(1000., 16).div_exact(Fixed32::new(1, 16)),
+            None
+        );
+    }
+
+    #[test]
+    fn test_count_significant_bits() {
+        assert_eq!(Fixed32::new(0, 16).count_significant_bits(), 0);
+        assert_eq!(Fixed32::new(1, 16).count_significant_bits(), 1);
+        assert_eq!(Fixed32::new(0b1011, 16).count_significant_bits(), 4);
+        assert_eq!(Fixed32::from(1., 16).count_significant_bits(), 17);
+        assert_eq!(Fixed32::new(i32::MAX, 16).count_significant_bits(), 31);
+        assert_eq!(Fixed32::new(-1, 16).count_significant_bits(), 32);
+    }
+
+    #[test]
+    fn test_get_leading_one_index() {
+        assert_eq!(Fixed32::new(0, 16).get_leading_one_index(), 0);
+        assert_eq!(Fixed32::new(1, 16).get_leading_one_index(), 0);
+        assert_eq!(Fixed32::new(0b1011, 16).get_leading_one_index(), 3);
+        assert_eq!(Fixed32::new(i32::MAX, 16).get_leading_one_index(), 30);
+    }
+
+    #[test]
+    fn test_to_normalized_mantissa_exp() {
+        assert_eq!(
+            Fixed32::from(3., 16).to_normalized_mantissa_exp(),
+            (0.75, 2)
+        );
+        assert_eq!(
+            Fixed32::from(-0.25, 16).to_normalized_mantissa_exp(),
+            (-0.5, -1)
+        );
+        assert_eq!(Fixed32::new(0, 16).to_normalized_mantissa_exp(), (0., 0));
+        assert_eq!(
+            Fixed32::new(i32::MIN, 0).to_normalized_mantissa_exp(),
+            (-0.5, 32)
+        );
+        assert_eq!(
+            Fixed32::new(i32::MAX, 0).to_normalized_mantissa_exp(),
+            (0.5, 32)
+        );
+
+        for x in [3.15, -107.4, 0.008375, 1e-4, 100.] {
+            let x = Fixed32::from(x, 24);
+            let (m, e) = x.to_normalized_mantissa_exp();
+            assert!((0.5..1.).contains(&m.abs()));
+            assert_eq!(m * 2f32.powi(e), x.to_f32());
+        }
+    }
+
+    #[test]
+    fn test_leading_significant_bit() {
+        assert_eq!(Fixed32::new(0, 16).leading_significant_bit(), 0);
+        assert_eq!(Fixed32::new(1, 16).leading_significant_bit(), 0);
+        assert_eq!(Fixed32::new(0b1011, 16).leading_significant_bit(), 3);
+        assert_eq!(Fixed32::new(-1, 16).leading_significant_bit(), 0);
+        assert_eq!(Fixed32::new(-0b1011, 16).leading_significant_bit(), 3);
+        assert_eq!(Fixed32::new(-i32::MAX, 16).leading_significant_bit(), 30);
+        assert_eq!(Fixed32::new(i32::MIN, 16).leading_significant_bit(), 31);
+    }
+
+    #[test]
+    fn test_is_power_of_two() {
+        assert!(Fixed32::from(1., 16).is_power_of_two());
+        assert!(Fixed32::from(0.125, 16).is_power_of_two());
+        assert!(Fixed32::new(1, 16).is_power_of_two());
+        assert!(Fixed32::new(1 << 30, 16).is_power_of_two());
+        assert!(!Fixed32::from(3., 16).is_power_of_two());
+        assert!(!Fixed32::new(0, 16).is_power_of_two());
+        assert!(!Fixed32::from(-2., 16).is_power_of_two());
+        assert!(!Fixed32::new(i32::MIN, 16).is_power_of_two());
+    }
+
+    #[test]
+    fn test_nearest_power_of_two() {
+        let nearest = |x: f32| Fixed32::from(x, 16).nearest_power_of_two();
+        assert_eq!(nearest(1.), Fixed32::from(1., 16));
+        assert_eq!(nearest(5.), Fixed32::from(4., 16));
+        assert_eq!(nearest(6.), Fixed32::from(8., 16));
+        assert_eq!(nearest(7.9), Fixed32::from(8., 16));
+        assert_eq!(nearest(0.3), Fixed32::from(0.25, 16));
+        assert_eq!(
+            Fixed32::new(1, 16).nearest_power_of_two(),
+            Fixed32::new(1, 16)
+        );
+        assert_eq!(
+            Fixed32::new(i32::MAX, 16).nearest_power_of_two(),
+            Fixed32::new(1 << 30, 16)
+        );
+        assert!(nearest(100.).is_power_of_two());
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_nearest_power_of_two_rejects_zero() {
+        Fixed32::new(0, 16).nearest_power_of_two();
+    }
+
+    #[test]
+    fn test_sum() {
+        let xs = [
+            Fixed32::from(1.5, 16),
+            Fixed32::from(-0.25, 16),
+            Fixed32::from(3.125, 16),
+        ];
+        let total = xs.iter().sum::<Fixed32>();
+        assert_eq!(total, Fixed32::from(4.375, 16));
+
+        let mixed = [Fixed32::from(1., 8), Fixed32::from(0.5, 16)];
+        assert_eq!(mixed.iter().sum::<Fixed32>(), Fixed32::from(1.5, 16));
+
+        let empty: [Fixed32; 0] = [];
+        assert_eq!(empty.iter().sum::<Fixed32>(), Fixed32::new(0, 0));
+
+        // The running sum passes i32::MAX before coming back into range
+        let large = [30000., 30000., -30000.].map(|x| Fixed32::from(x, 16));
+        assert_eq!(large.iter().sum::<Fixed32>(), Fixed32::from(30000., 16));
+    }
+
+    #[test]
+    fn test_product() {
+        let xs = [
+            Fixed32::from(1.5, 16),
+            Fixed32::from(-2., 16),
+            Fixed32::from(0.5, 16),
+        ];
+        assert_eq!(xs.iter().product::<Fixed32>(), Fixed32::from(-1.5, 16));
+
+        let empty: [Fixed32; 0] = [];
+        assert_eq!(empty.iter().product::<Fixed32>().to_f32(), 1.);
+    }
+
+    #[test]
+    fn test_signum_fixed() {
+        for (value, sign) in [(2.75, 1.), (-2.75, -1.), (0., 0.)] {
+            let x = Fixed32::from(value, 16);
+            assert_eq!(x.signum_fixed(), Fixed32::from(sign, 16));
+            assert_eq!(x * x.signum_fixed(), x.abs());
+        }
+    }
+
+    #[test]
+    fn test_negate_if() {
+        let x = Fixed32::from(2.75, 16);
+        assert_eq!(x.negate_if(true), Fixed32::from(-2.75, 16));
+        assert_eq!(x.negate_if(false), x);
+
+        for value in [3.15, -0.5, 0.] {
+            let x = Fixed32::from(value, 16);
+            for cond in [true, false] {
+                let branching = if cond { -x } else { x };
+                assert_eq!(x.negate_if(cond), branching);
+            }
+        }
+    }
+
+    #[test]
+    fn test_from_angle_degrees() {
+        let pi = Fixed32::from_angle_degrees(180., 24);
+        let expected = (std::f64::consts::PI * (1 << 24) as f64).round() as i32;
+        assert!((pi.value() - expected).abs() <= 1);
+
+        // One ULP of an `f32` in [2, 4) is twice the machine epsilon
+        let error = (pi.to_f32() - std::f32::consts::PI).abs();
+        assert!(error <= 2. * f32::EPSILON);
+
+        let quarter = Fixed32::from_angle_degrees(-45., 24).to_f32();
+        assert!((quarter + std::f32::consts::FRAC_PI_4).abs() < 1e-6);
+    }
 
+    #[test]
+    fn test_to_degrees() {
+        // Rounding the radians to 16 bits costs up to 0.5 * 180 / π ULPs
+        let angle = Fixed32::from_angle_degrees(90., 16).to_degrees();
+        let tolerance = Fixed32::new(29, 16);
+        assert!(angle.approx_eq(Fixed32::from(90., 16), tolerance));
+
+        let radian = Fixed32::from(1., 16).to_degrees();
         assert!(
-            (result_float - expected_result).abs() < 0.1,
-            "test case 1 failed: got {}, expected {}",
-            result_float,
-            expected_result
+            Fixed32::from(57.29578, 16).approx_eq_within_percent(radian, 0.01),
+            "got {}, expected 57.29578 ({:.3}% error)",
+            radian.to_f32(),
+            relative_error_percent(57.29578, radian.to_f32())
+        );
+    }
+
+    #[test]
+    fn test_approx_reciprocal_table() {
+        let one = Fixed32::from(1., 24);
+        for x in Fixed32::iter_range(one, Fixed32::from(127., 24), one) {
+            let result = x.approx_reciprocal_table().unwrap();
+            assert_eq!(result.exp(), 24);
+            assert!(
+                (result.to_f32() - 1. / x.to_f32()).abs()
+                    <= 1. / (1 << 24) as f32
+            );
+        }
+
+        let x = Fixed32::from(255., 16);
+        let result = x.approx_reciprocal_table().unwrap();
+        assert_eq!(result, Fixed32::from(1. / 255., 16));
+    }
+
+    #[test]
+    fn test_approx_reciprocal_table_out_of_range() {
+        assert_eq!(Fixed32::from(0., 16).approx_reciprocal_table(), None);
+        assert_eq!(Fixed32::from(2.5, 16).approx_reciprocal_table(), None);
+        assert_eq!(Fixed32::from(-4., 16).approx_reciprocal_table(), None);
+        assert_eq!(Fixed32::from(256., 16).approx_reciprocal_table(), None);
+        assert_eq!(Fixed32::from(3., 25).approx_reciprocal_table(), None);
+    }
+
+    #[test]
+    #[ignore]
+    fn bench_approx_reciprocal_table() {
+        // Run with `cargo test --release -- --ignored`
+        use crate::measure::measure_duration;
+
+        let values: Vec<Fixed32> = (1..=255)
+            .cycle()
+            .take(100000)
+            .map(|n| Fixed32::from(n as f32, 16))
+            .collect();
+        let (iterative, iterative_time) = measure_duration(|| {
+            values.iter().map(|x| x.reciprocal()).collect::<Vec<_>>()
+        });
+        let (table, table_time) = measure_duration(|| {
+            values
+                .iter()
+                .map(|x| x.approx_reciprocal_table().unwrap())
+                .collect::<Vec<_>>()
+        });
+
+        assert_eq!(iterative.len(), table.len());
+        println!(
+            "newton-raphson: {:.3?}, table: {:.3?}, speedup: {:.2}x",
+            iterative_time,
+            table_time,
+            iterative_time.as_secs_f64() / table_time.as_secs_f64()
+        );
+    }
+
+    #[test]
+    fn test_cached_div() {
+        let divisors = [3., 0.5, 3., 107.4].map(|x| Fixed32::from(x, 24));
+        let table = Fixed32::precompute_reciprocal_table(&divisors);
+        assert_eq!(table.len(), 3);
+        assert_eq!(table[&divisors[0].value()], divisors[0].reciprocal());
+
+        let numerator = Fixed32::from(1.25, 24);
+        for divisor in divisors {
+            assert_eq!(
+                Fixed32::cached_div(numerator, divisor, &table),
+                numerator / divisor
+            );
+        }
+
+        // Divisors missing from the table, or at another exponential, are
+        // computed on the fly
+        let divisor = Fixed32::from(5., 24);
+        assert_eq!(
+            Fixed32::cached_div(numerator, divisor, &table),
+            numerator / divisor
+        );
+        let (numerator, divisor) =
+            (Fixed32::from(1.25, 16), Fixed32::from(3., 16));
+        assert_eq!(
+            Fixed32::cached_div(numerator, divisor, &table),
+            numerator / divisor
+        );
+    }
+
+    #[test]
+    #[ignore]
+    fn bench_cached_div() {
+        // Run with `cargo test --release -- --ignored`
+        use crate::measure::measure_duration;
+
+        let divisors: Vec<Fixed32> = (1..=16)
+            .map(|n| Fixed32::from(n as f32 * 0.75, 16))
+            .collect();
+        let table = Fixed32::precompute_reciprocal_table(&divisors);
+        let pairs: Vec<(Fixed32, Fixed32)> = (0..100000)
+            .map(|i| {
+                let numerator = Fixed32::from((i % 1000) as f32 / 10., 16);
+                (numerator, divisors[i % divisors.len()])
+            })
+            .collect();
+        let (uncached, uncached_time) = measure_duration(|| {
+            pairs.iter().map(|&(n, d)| n / d).collect::<Vec<_>>()
+        });
+        let (cached, cached_time) = measure_duration(|| {
+            pairs
+                .iter()
+                .map(|&(n, d)| Fixed32::cached_div(n, d, &table))
+                .collect::<Vec<_>>()
+        });
+
+        assert_eq!(uncached, cached);
+        println!(
+            "uncached: {:.3?}, cached: {:.3?}, speedup: {:.2}x",
+            uncached_time,
+            cached_time,
+            uncached_time.as_secs_f64() / cached_time.as_secs_f64()
+        );
+    }
+
+    #[test]
+    fn test_to_q_format_string() {
+        assert_eq!(
+            Fixed32::new(0x00C00000, 24).to_q_format_string(),
+            "Q8.24: 0.75"
+        );
+        assert_eq!(
+            Fixed32::from(-3.5, 16).to_q_format_string(),
+            "Q16.16: -3.5"
+        );
+        assert_eq!(
+            Fixed32::new(1, 31).to_q_format_string(),
+            "Q1.31: 0.0000000004656612873077393"
+        );
+    }
+
+    #[test]
+    fn test_q_format_round_trip() {
+        for x in [
+            Fixed32::new(0x00C00000, 24),
+            Fixed32::from(-3.15, 16),
+            Fixed32::new(i32::MIN, 31),
+            Fixed32::new(i32::MAX, 0),
+            Fixed32::new(0x12345, 20),
+        ] {
+            assert_eq!(
+                Fixed32::from_q_format_str(&x.to_q_format_string()),
+                Ok(x)
+            );
+        }
+        assert_eq!(
+            Fixed32::from_q_format_str("Q8.24: 0.75"),
+            Ok(Fixed32::new(0x00C00000, 24))
+        );
+    }
+
+    #[test]
+    fn test_from_q_format_str_errors() {
+        assert_eq!(Fixed32::from_q_format_str(""), Err(ParseFixedError::Empty));
+        for s in [
+            "8.24: 0.75",
+            "Q8.24 0.75",
+            "Q8.23: 0.75",
+            "Qa.24: 1",
+            "Q8.24: x",
+        ] {
+            assert_eq!(
+                Fixed32::from_q_format_str(s),
+                Err(ParseFixedError::InvalidFormat)
+            );
+        }
+        assert_eq!(
+            Fixed32::from_q_format_str("Q8.24: 200"),
+            Err(ParseFixedError::Overflow)
+        );
+    }
+
+    #[test]
+    fn test_iter_range() {
+        let range: Vec<Fixed32> = Fixed32::iter_range(
+            Fixed32::from(-1., 16),
+            Fixed32::from(1., 16),
+            Fixed32::from(0.25, 16),
+        )
+        .collect();
+        assert_eq!(range.len(), 9);
+        assert_eq!(range[0], Fixed32::from(-1., 16));
+        assert_eq!(range[4], Fixed32::new(0, 16));
+        assert_eq!(range[8], Fixed32::from(1., 16));
+
+        // `end` is not included unless a step lands on it
+        let step = Fixed32::from(0.3, 16);
+        let range: Vec<Fixed32> = Fixed32::iter_range(
+            Fixed32::new(0, 16),
+            Fixed32::from(1., 16),
+            step,
+        )
+        .collect();
+        assert_eq!(range.len(), 4);
+        assert_eq!(*range.last().unwrap(), Fixed32::new(3 * step.value(), 16));
+
+        let max = Fixed32::new(i32::MAX, 0);
+        let range = Fixed32::iter_range(
+            Fixed32::new(i32::MAX - 2, 0),
+            max,
+            Fixed32::new(2, 0),
+        );
+        assert_eq!(range.count(), 2);
+        let empty =
+            Fixed32::iter_range(max, Fixed32::new(0, 0), Fixed32::new(1, 0));
+        assert_eq!(empty.count(), 0);
+    }
+
+    #[test]
+    fn test_midpoint() {
+        let half_max = Fixed32::new(i32::MAX / 2, 16);
+        let cases = [
+            (Fixed32::from(1., 16), Fixed32::from(2., 16)),
+            (Fixed32::from(-1.5, 16), Fixed32::from(0.25, 16)),
+            (half_max, Fixed32::new(i32::MAX / 2 + 3, 16)),
+            (half_max, Fixed32::new(i32::MAX, 16)),
+            (Fixed32::new(i32::MAX, 16), Fixed32::new(i32::MAX, 16)),
+            (Fixed32::new(i32::MIN, 16), Fixed32::new(i32::MAX, 16)),
+            (Fixed32::new(-3, 16), Fixed32::new(0, 16)),
+        ];
+        for (a, b) in cases {
+            assert_eq!(Fixed32::midpoint(a, b), Fixed32::midpoint_i64(a, b));
+            assert_eq!(Fixed32::midpoint(a, b), Fixed32::midpoint(b, a));
+        }
+
+        assert_eq!(
+            Fixed32::midpoint(cases[0].0, cases[0].1),
+            Fixed32::from(1.5, 16)
+        );
+        assert_eq!(
+            Fixed32::midpoint(half_max, Fixed32::new(i32::MAX / 2 + 3, 16)),
+            Fixed32::new(i32::MAX / 2 + 1, 16)
+        );
+        assert_eq!(
+            Fixed32::midpoint(
+                Fixed32::new(i32::MIN, 16),
+                Fixed32::new(i32::MAX, 16)
+            ),
+            Fixed32::new(-1, 16)
+        );
+    }
+
+    #[test]
+    fn test_two_sum_exact_without_overflow() {
+        let a = Fixed32::from(1.5, 16);
+        let b = Fixed32::from(-0.25, 8);
+        let (sum, wraps) = Fixed32::two_sum_exact(a, b);
+        assert_eq!(sum, a + b);
+        assert_eq!(wraps, 0);
+    }
+
+    #[test]
+    fn test_overflowing_add() {
+        let a = Fixed32::from(1.5, 16);
+        let b = Fixed32::from(-2.25, 16);
+        assert_eq!(a.overflowing_add(b), (Fixed32::from(-0.75, 16), false));
+
+        let max = Fixed32::new(i32::MAX, 16);
+        let one_ulp = Fixed32::new(1, 16);
+        assert_eq!(
+            max.overflowing_add(one_ulp),
+            (Fixed32::new(i32::MIN, 16), true)
+        );
+        assert_eq!(
+            Fixed32::new(i32::MIN, 16).overflowing_add(Fixed32::new(-1, 16)),
+            (max, true)
+        );
+    }
+
+    #[test]
+    fn test_overflowing_mul() {
+        let a = Fixed32::from(1.5, 16);
+        let b = Fixed32::from(-2.25, 16);
+        assert_eq!(a.overflowing_mul(b), (a * b, false));
+        assert_eq!(a.overflowing_mul(b).0, Fixed32::from(-3.375, 16));
+
+        // 256 * 128 = 2^15 is just outside Q16.16
+        let (product, overflow) =
+            Fixed32::from(256., 16).overflowing_mul(Fixed32::from(128., 16));
+        assert!(overflow);
+        assert_eq!(product, Fixed32::new(i32::MIN, 16));
+
+        let (product, overflow) =
+            Fixed32::from(-256., 16).overflowing_mul(Fixed32::from(128., 16));
+        assert!(!overflow);
+        assert_eq!(product, Fixed32::new(i32::MIN, 16));
+
+        // Almost 2 squared does not fit at exp 30, but 1 squared does
+        let max = Fixed32::new(i32::MAX, 30);
+        assert!(max.overflowing_mul(max).1);
+        let one = Fixed32::from(1., 30);
+        assert_eq!(one.overflowing_mul(one), (one, false));
+        let (product, overflow) =
+            Fixed32::new(1 << 16, 0).overflowing_mul(Fixed32::new(1 << 15, 0));
+        assert!(overflow);
+        assert_eq!(product, Fixed32::new(i32::MIN, 0));
+    }
+
+    #[test]
+    fn test_two_sum_exact_with_overflow() {
+        for (a, b, expected_wraps) in [
+            (Fixed32::new(i32::MAX, 24), Fixed32::new(1, 24), 1),
+            (Fixed32::new(i32::MIN, 24), Fixed32::new(-5, 24), -1),
+            (Fixed32::new(i32::MAX, 24), Fixed32::new(i32::MAX, 24), 1),
+            (Fixed32::new(1 << 20, 24), Fixed32::new(1 << 20, 12), 1),
+        ] {
+            let (sum, wraps) = Fixed32::two_sum_exact(a, b);
+            assert_eq!(sum.exp(), 24);
+            assert_eq!(wraps, expected_wraps);
+
+            // a + b == sum + wraps * 2^32 in units of 2^-24
+            let exact = a.value_at_exp(24) + b.value_at_exp(24);
+            assert_eq!(sum.value() as i64 + ((wraps as i64) << 32), exact);
+        }
+    }
+
+    #[test]
+    fn test_double_precision_mul() {
+        for (a, b) in [(3.15, 0.7), (-2.5, 1.3), (-0.001, -123.4), (7., 8.)] {
+            let (a, b) = (Fixed32::from(a, 16), Fixed32::from(b, 16));
+            let (hi, lo) = Fixed32::double_precision_mul(a, b);
+            assert_eq!(hi, a * b);
+            assert_eq!(lo.exp(), 32);
+            assert!(lo.value() >= 0 && lo.value() < 1 << 16);
+
+            let expected = a.value() as i64 * b.value() as i64;
+            assert_eq!(Fixed32::combine_double_precision(hi, lo), expected);
+        }
+
+        // With small exponentials the halves can be added directly
+        for (a, b) in [(3.15, 0.7), (-2.5, 1.3), (-0.1, -12.3)] {
+            let (a, b) = (Fixed32::from(a, 8), Fixed32::from(b, 8));
+            let (hi, lo) = Fixed32::double_precision_mul(a, b);
+            let expected = a.value() as i64 * b.value() as i64;
+            assert_eq!(hi + lo, Fixed32::new(expected as i32, 16));
+        }
+    }
+
+    #[test]
+    fn test_from_q15() {
+        assert_eq!(Fixed32::from_q15(i16::MIN).to_f32(), -1.);
+        assert_eq!(Fixed32::from_q15(0x4000).to_f32(), 0.5);
+        assert_eq!(Fixed32::from_q15(i16::MAX).to_f32(), 1. - 1. / 32768.);
+        assert_eq!(Fixed32::from_q15(-1).exp(), 15);
+    }
+
+    #[test]
+    fn test_to_q15() {
+        // +1.0 is just outside the Q15 range and saturates
+        assert_eq!(Fixed32::from(1., 24).to_q15(), i16::MAX);
+        assert_eq!(Fixed32::from(-1., 24).to_q15(), i16::MIN);
+        assert_eq!(Fixed32::from(1. - 1. / 32768., 16).to_q15(), i16::MAX);
+        assert_eq!(Fixed32::from(-0.25, 8).to_q15(), -0x2000);
+        assert_eq!(Fixed32::from(100., 16).to_q15(), i16::MAX);
+        assert_eq!(Fixed32::from(-100., 16).to_q15(), i16::MIN);
+        for value in [i16::MIN, -12345, 0, 1, i16::MAX] {
+            assert_eq!(Fixed32::from_q15(value).to_q15(), value);
+        }
+    }
+
+    #[test]
+    fn test_u8_array_round_trip() {
+        let bytes: Vec<u8> = (0..=255).collect();
+        for exp in [8, 16, 24] {
+            let values = Fixed32::from_u8_array(&bytes, exp);
+            assert!(values.iter().all(|x| x.exp() == exp));
+            assert_eq!(values[0], Fixed32::new(0, exp));
+            assert_eq!(values[255], Fixed32::from(1., exp));
+            assert_eq!(Fixed32::to_u8_array(&values), bytes);
+        }
+    }
+
+    #[test]
+    fn test_to_u8_array_clamps() {
+        let values = [
+            Fixed32::from(-0.5, 16),
+            Fixed32::from(0.5, 16),
+            Fixed32::from(3., 16),
+        ];
+        assert_eq!(Fixed32::to_u8_array(&values), [0, 128, 255]);
+    }
+
+    #[test]
+    fn test_q31() {
+        assert_eq!(Fixed32::from_q31(i32::MIN).exp(), 31);
+        assert_eq!(Fixed32::from_q31(1 << 30).to_q31(), 1 << 30);
+        assert_eq!(Fixed32::from(0.5, 24).to_q31(), 1 << 30);
+        assert_eq!(Fixed32::from(-1., 24).to_q31(), i32::MIN);
+        assert_eq!(Fixed32::from(1., 24).to_q31(), i32::MAX);
+        assert_eq!(Fixed32::from(-3., 16).to_q31(), i32::MIN);
+    }
+
+    #[test]
+    fn test_q16() {
+        assert_eq!(Fixed32::from_q16(0x0003_8000).to_f32(), 3.5);
+        assert_eq!(Fixed32::from(-2.25, 24).to_q16(), -0x0002_4000);
+        assert_eq!(Fixed32::from(100., 4).to_q16(), 100 << 16);
+        assert_eq!(Fixed32::new(i32::MAX, 0).to_q16(), i32::MAX);
+        assert_eq!(Fixed32::new(i32::MIN, 8).to_q16(), i32::MIN);
+    }
+
+    #[test]
+    fn test_fixed_accumulator() {
+        // The running sum reaches 2^19 * 30000, far beyond 32 bits, but the
+        // total of 2^19 ULPs fits again
+        let count = 1 << 20;
+        let mut accumulator = FixedAccumulator::new(16);
+        for i in 0..count {
+            let value = if i < count / 2 {
+                Fixed32::new((30000 << 16) + 1, 16)
+            } else {
+                Fixed32::from(-30000., 16)
+            };
+            accumulator.add(value);
+        }
+
+        assert_eq!(accumulator.count(), count as u64);
+        assert_eq!(accumulator.finish(), Some(Fixed32::from(8., 16)));
+    }
+
+    #[test]
+    fn test_fixed_accumulator_overflow() {
+        let mut accumulator = FixedAccumulator::new(16);
+        for _ in 0..1 << 20 {
+            accumulator.add(Fixed32::from(0.5, 16));
+        }
+        assert_eq!(accumulator.finish(), None);
+    }
+
+    #[test]
+    fn test_sum_slice() {
+        let values = vec![Fixed32::from(1. / 1024., 16); 1 << 20];
+        assert_eq!(
+            FixedAccumulator::sum_slice(&values),
+            Fixed32::from(1024., 16)
+        );
+        assert_eq!(FixedAccumulator::sum_slice(&[]), Fixed32::new(0, 0));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_fixed_accumulator_exp_mismatch() {
+        let mut accumulator = FixedAccumulator::new(16);
+        accumulator.add(Fixed32::from(1., 8));
+    }
+
+    #[test]
+    fn test_from_f64_clamped() {
+        // 3.15 * 2^24 = 52848230.4, which `f32` cannot represent
+        assert_eq!(Fixed32::from_f64_clamped(3.15, 24).value(), 52848230);
+        assert_eq!(
+            Fixed32::from_f64_clamped(f64::INFINITY, 16).value(),
+            i32::MAX
+        );
+        assert_eq!(
+            Fixed32::from_f64_clamped(f64::NEG_INFINITY, 16).value(),
+            i32::MIN
+        );
+        assert_eq!(Fixed32::from_f64_clamped(1e300, 0).value(), i32::MAX);
+        assert_eq!(Fixed32::from_f64_clamped(f64::NAN, 16).value(), 0);
+        assert_eq!(Fixed32::from_f64_clamped(-0.5, 31).value(), -(1 << 30));
+    }
+
+    #[test]
+    fn test_from_clamps_out_of_range() {
+        assert_eq!(Fixed32::from(f32::INFINITY, 24).value(), i32::MAX);
+        assert_eq!(Fixed32::from(f32::NEG_INFINITY, 24).value(), i32::MIN);
+        assert_eq!(Fixed32::from(1e38, 16).value(), i32::MAX);
+        assert_eq!(Fixed32::from(-1e38, 16).value(), i32::MIN);
+        assert_eq!(Fixed32::from_f64_clamped(0.75, 31).value(), 3 << 29);
+    }
+
+    #[test]
+    fn test_f32_scalar_ops() {
+        let x = Fixed32::from(1.5, 16);
+        assert_eq!(x + 0.25, Fixed32::from(1.75, 16));
+        assert_eq!(x - 2., Fixed32::from(-0.5, 16));
+        assert_eq!(x * -3., Fixed32::from(-4.5, 16));
+        assert!((x / 4.).approx_eq_ulps(Fixed32::from(0.375, 16), 1));
+
+        assert_eq!(0.25 + x, Fixed32::from(1.75, 16));
+        assert_eq!(2. - x, Fixed32::from(0.5, 16));
+        assert_eq!(-3. * x, Fixed32::from(-4.5, 16));
+        // Division goes through the truncated `reciprocal`
+        assert!((3. / x).approx_eq_ulps(Fixed32::from(2., 16), 2));
+        assert_eq!((x + 0.25).exp(), 16);
+    }
+
+    #[test]
+    fn test_from_scientific_notation() {
+        let x = Fixed32::from_scientific_notation(1.2345678, 5, 8);
+        assert_eq!(
+            x.value(),
+            (1.2345678f32 as f64 * 1e5 * 256.).round() as i32
+        );
+        assert_ne!(x, Fixed32::from(1.2345678e5, 8));
+        assert_eq!(
+            Fixed32::from_scientific_notation(-2.5, 0, 16),
+            Fixed32::from(-2.5, 16)
+        );
+        assert_eq!(
+            Fixed32::from_scientific_notation(1.5, -3, 24),
+            Fixed32::from(0.0015, 24)
+        );
+
+        // The gravitational constant is below the resolution of exponent 30,
+        // but keeps 29 significant bits as a raw value at exponent 62
+        assert_eq!(
+            Fixed32::from_scientific_notation(6.674, -11, 30).value(),
+            0
+        );
+        let g = Fixed32::from_scientific_notation(6.674, -11, 62);
+        let exact = 6.674f32 as f64 * 1e-11 * 2f64.powi(62);
+        assert_eq!(g.value(), exact.round() as i32);
+        assert!((g.value() as f64 / 2f64.powi(62) - 6.674e-11).abs() < 1e-17);
+        assert_ne!(g, Fixed32::from_f64_clamped(6.674e-11f32 as f64, 62));
+    }
+
+    #[test]
+    fn test_valid_exp() {
+        assert_eq!(Fixed32::from(1., 30).value(), 1 << 30);
+        assert_eq!(Fixed32::from(-1., 30).to_f32(), -1.);
+        assert!(valid_exp(0) && valid_exp(30));
+        assert!(!valid_exp(31) && !valid_exp(-1));
+    }
+
+    #[cfg(debug_assertions)]
+    #[test]
+    #[should_panic(expected = "The exponential must be between 0 and 30!")]
+    fn test_from_rejects_exp_31() {
+        Fixed32::from(0.5, 31);
+    }
+
+    #[test]
+    fn test_bit_vector() {
+        let bits = Fixed32::new(0b1011, 16).to_bit_vector();
+        assert_eq!(bits[..5], [true, true, false, true, false]);
+        assert!(bits[4..].iter().all(|&bit| !bit));
+        assert!(Fixed32::new(-1, 16).to_bit_vector().iter().all(|&bit| bit));
+        assert!(Fixed32::new(i32::MIN, 16).to_bit_vector()[31]);
+    }
+
+    #[test]
+    fn test_bit_vector_round_trip() {
+        for value in [0., 3.15, -3.15, 1000.5, -0.0001] {
+            let x = Fixed32::from(value, 16);
+            assert_eq!(Fixed32::from_bit_vector(x.to_bit_vector(), 16), x);
+        }
+        for raw in [i32::MIN, i32::MAX, -1, 1] {
+            let x = Fixed32::new(raw, 24);
+            assert_eq!(Fixed32::from_bit_vector(x.to_bit_vector(), 24), x);
+        }
+    }
+
+    #[test]
+    fn test_div_remainder() {
+        for (a, b) in [
+            (7.5, 2.),
+            (-7.5, 2.),
+            (7.5, -2.),
+            (-7.5, -2.),
+            (3.15, 0.7),
+            (0.25, 3.),
+        ] {
+            let (a, b) = (Fixed32::from(a, 16), Fixed32::from(b, 16));
+            let (quotient, remainder) = a.div_remainder(b).unwrap();
+            assert_eq!(quotient * b + remainder, a);
+            assert_eq!(quotient.value() % (1 << 16), 0);
+            assert!(remainder.abs().value() < b.abs().value());
+            assert!(
+                remainder.value() == 0
+                    || remainder.value().signum() == a.value().signum()
+            );
+        }
+
+        let (quotient, remainder) = Fixed32::from(-7.5, 16)
+            .div_remainder(Fixed32::from(2., 16))
+            .unwrap();
+        assert_eq!((quotient.to_f32(), remainder.to_f32()), (-3., -1.5));
+    }
+
+    #[test]
+    fn test_div_remainder_errors() {
+        let x = Fixed32::from(1., 16);
+        assert_eq!(
+            x.div_remainder(Fixed32::new(0, 16)),
+            Err(ArithmeticError::DivisionByZero)
+        );
+        assert_eq!(
+            Fixed32::from(1000., 16).div_remainder(Fixed32::new(1, 16)),
+            Err(ArithmeticError::Overflow)
         );
+        assert_eq!(
+            Fixed32::new(i32::MIN, 16).div_remainder(Fixed32::new(-1, 16)),
+            Err(ArithmeticError::Overflow)
+        );
+    }
+
+    #[test]
+    fn test_to_fixed32_at_exp() {
+        // Values in units of 2^-2, rounded to integers (exp 0)
+        let cases = [
+            (2.25, [2., 3., 2., 2., 2.]),
+            (2.5, [2., 3., 2., 3., 2.]),
+            (3.5, [3., 4., 3., 4., 4.]),
+            (2.75, [2., 3., 2., 3., 3.]),
+            (-2.25, [-3., -2., -2., -2., -2.]),
+            (-2.5, [-3., -2., -2., -3., -2.]),
+            (-3.5, [-4., -3., -3., -4., -4.]),
+            (-2., [-2., -2., -2., -2., -2.]),
+        ];
+        let modes = [
+            RoundingMode::Floor,
+            RoundingMode::Ceil,
+            RoundingMode::TowardZero,
+            RoundingMode::HalfAwayFromZero,
+            RoundingMode::HalfToEven,
+        ];
+        for (value, expected) in cases {
+            for (mode, expected) in modes.iter().zip(expected) {
+                let result =
+                    Fixed32::from(value, 2).to_fixed32_at_exp(0, *mode);
+                assert_eq!(
+                    result,
+                    Fixed32::from(expected, 0),
+                    "{} {:?}",
+                    value,
+                    mode
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_to_fixed32_at_exp_raises_exactly() {
+        let x = Fixed32::from(-3.15, 16);
+        for mode in [RoundingMode::Floor, RoundingMode::HalfToEven] {
+            let raised = x.to_fixed32_at_exp(24, mode);
+            assert_eq!(raised.exp(), 24);
+            assert_eq!(raised.to_f32(), x.to_f32());
+            assert_eq!(raised.to_fixed32_at_exp(16, mode), x);
+        }
     }
 }
@@ -0,0 +1,286 @@
+use crate::fixed::{
+    ConversionError,
+    Fixed32,
+};
+use std::ops::{
+    Add,
+    Div,
+    Mul,
+    Sub,
+};
+
+// The largest exponent used when widening to `Fixed32` for Newton-Raphson
+const WIDE_EXP: i32 = 24;
+
+// The largest exponent that still leaves room for the sign bit in an `i16`
+const MAX_EXP: i32 = 15;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Fixed16 {
+    // Stores the integer representing of the fixed-point value. The
+    // fixed-point representation is scaled based on the `exp` field.
+    value: i16,
+
+    // The exponent used to determine the scaling factor of the fixed-point
+    // number. It represents the negative power of 2 used to scale the value.
+    exp: u8,
+}
+
+impl Fixed16 {
+    pub fn new(value: i16, exp: u8) -> Self {
+        Self { value, exp }
+    }
+
+    pub fn value(self) -> i16 {
+        self.value
+    }
+
+    pub fn exp(self) -> u8 {
+        self.exp
+    }
+
+    pub fn from<T: Into<f32>>(value: T, exp: u8) -> Self {
+        // Converts a floating-point number into a fixed-point number
+        let val: f32 = value.into() * (1 << exp) as f32;
+        Self {
+            value: val.round() as i16,
+            exp,
+        }
+    }
+
+    pub fn to_f32(self) -> f32 {
+        // Converts a fixed-point number to a floating-point number
+        self.value as f32 / (1 << self.exp) as f32
+    }
+
+    pub fn reciprocal(self) -> Self {
+        // Widens to `Fixed32` so that Newton-Raphson has enough headroom,
+        // then narrows the result back to `exp`. Large values get fewer
+        // fractional bits so that the leading one stays below bit 30
+        let magnitude = (self.value as i32).unsigned_abs().max(1);
+        let leading_one_index = 31 - magnitude.leading_zeros() as i32;
+        let shift = (WIDE_EXP - self.exp as i32).min(29 - leading_one_index);
+        let wide =
+            Fixed32::new((self.value as i32) << shift, self.exp as i32 + shift);
+        let result = wide.reciprocal();
+
+        Fixed16::new((result.value() >> shift) as i16, self.exp)
+    }
+}
+
+impl From<Fixed16> for Fixed32 {
+    fn from(value: Fixed16) -> Self {
+        // Widening never loses information
+        Fixed32::new(value.value as i32, value.exp as i32)
+    }
+}
+
+impl TryFrom<Fixed32> for Fixed16 {
+    type Error = ConversionError;
+
+    fn try_from(value: Fixed32) -> Result<Self, Self::Error> {
+        // Keeps the exponent when possible; otherwise drops fractional bits
+        // down to `MAX_EXP`, which is only allowed if they are all zero
+        let (raw, exp) = if value.exp() < 0 {
+            let shifted = (value.value() as i64) << -value.exp();
+            (shifted, 0)
+        } else if value.exp() > MAX_EXP {
+            let shift = value.exp() - MAX_EXP;
+            if value.value() & ((1 << shift) - 1) != 0 {
+                return Err(ConversionError::PrecisionLoss);
+            }
+            ((value.value() >> shift) as i64, MAX_EXP)
+        } else {
+            (value.value() as i64, value.exp())
+        };
+
+        match i16::try_from(raw) {
+            Ok(raw) => Ok(Fixed16::new(raw, exp as u8)),
+            Err(_) => Err(ConversionError::Overflow),
+        }
+    }
+}
+
+impl Add for Fixed16 {
+    type Output = Fixed16;
+
+    fn add(self, other: Self) -> Self::Output {
+        if self.exp == other.exp {
+            Fixed16::new(self.value + other.value, self.exp)
+        } else if self.exp > other.exp {
+            let shift = self.exp - other.exp;
+            Fixed16::new(self.value + (other.value << shift), self.exp)
+        } else {
+            let shift = other.exp - self.exp;
+            Fixed16::new((self.value << shift) + other.value, other.exp)
+        }
+    }
+}
+
+impl Sub for Fixed16 {
+    type Output = Fixed16;
+
+    fn sub(self, other: Self) -> Self::Output {
+        if self.exp == other.exp {
+            Fixed16::new(self.value - other.value, self.exp)
+        } else if self.exp > other.exp {
+            let shift = self.exp - other.exp;
+            Fixed16::new(self.value - (other.value << shift), self.exp)
+        } else {
+            let shift = other.exp - self.exp;
+            Fixed16::new((self.value << shift) - other.value, other.exp)
+        }
+    }
+}
+
+impl Mul for Fixed16 {
+    type Output = Fixed16;
+
+    fn mul(self, other: Self) -> Self::Output {
+        if self.exp != other.exp {
+            panic!(
+                "Only support multiplication between two fixed-point \
+            numbers with the same exponential!"
+            )
+        }
+
+        let val1: i32 = self.value as i32;
+        let val2: i32 = other.value as i32;
+        let product: i32 = (val1 * val2) >> self.exp;
+
+        Fixed16 {
+            value: product as i16,
+            exp: self.exp,
+        }
+    }
+}
+
+impl Div for Fixed16 {
+    type Output = Fixed16;
+
+    fn div(self, other: Self) -> Self::Output {
+        if self.exp != other.exp {
+            panic!(
+                "Only support division between two fixed-point numbers with \
+            the same exponential!"
+            )
+        }
+
+        if other.value == 0 {
+            panic!("Division by zero error!");
+        }
+
+        self * other.reciprocal()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f32::consts::PI;
+
+    #[test]
+    fn test_from_to_f32() {
+        let x = Fixed16::from(PI, 10);
+        assert!((x.to_f32() - PI).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_add_different_exp() {
+        let a = Fixed16::new(10, 3);
+        let b = Fixed16::new(15, 2);
+        let result = a + b;
+        assert_eq!(result.value, 40);
+        assert_eq!(result.exp, 3);
+    }
+
+    #[test]
+    fn test_sub_same_exp() {
+        let a = Fixed16::new(20, 4);
+        let b = Fixed16::new(10, 4);
+        assert_eq!(a - b, Fixed16::new(10, 4));
+    }
+
+    #[test]
+    fn test_mul_same_exp() {
+        let a = Fixed16::from(2.5, 10);
+        let b = Fixed16::from(-3., 10);
+        assert_eq!((a * b).to_f32(), -7.5);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_mul_different_exp() {
+        let _result = Fixed16::new(10, 4) * Fixed16::new(20, 3);
+    }
+
+    #[test]
+    fn test_reciprocal() {
+        let result = Fixed16::from(PI, 10).reciprocal().to_f32();
+        assert!((result - 1. / PI).abs() < 0.002, "got {}", result);
+    }
+
+    #[test]
+    fn test_reciprocal_large() {
+        // At least 128, which overflows `Fixed32` at `WIDE_EXP`
+        for (x, exp) in [(130., 7), (150., 4), (-200., 6), (1000., 5)] {
+            let result = Fixed16::from(x, exp).reciprocal().to_f32();
+            let ulp = 1. / (1 << exp) as f32;
+            assert!(result * x >= 0., "got {} for {}", result, x);
+            assert!((result - 1. / x).abs() <= ulp, "got {} for {}", result, x);
+        }
+        assert_eq!(Fixed16::from(150., 4).reciprocal(), Fixed16::new(0, 4));
+    }
+
+    #[test]
+    fn test_div() {
+        let a = Fixed16::from(5., 10);
+        let b = Fixed16::from(4., 10);
+        let result = (a / b).to_f32();
+        assert!((result - 1.25).abs() < 0.005, "got {}", result);
+    }
+
+    #[test]
+    fn test_into_fixed32() {
+        let x = Fixed16::from(-3.25, 10);
+        let widened: Fixed32 = x.into();
+        assert_eq!(widened, Fixed32::new(-3328, 10));
+        assert_eq!(widened.to_f32(), x.to_f32());
+    }
+
+    #[test]
+    fn test_try_from_fixed32() {
+        assert_eq!(
+            Fixed16::try_from(Fixed32::from(3.25, 10)),
+            Ok(Fixed16::from(3.25, 10))
+        );
+        assert_eq!(
+            Fixed16::try_from(Fixed32::from(0.75, 24)),
+            Ok(Fixed16::from(0.75, 15))
+        );
+        assert_eq!(
+            Fixed16::try_from(Fixed32::new(3, -2)),
+            Ok(Fixed16::new(12, 0))
+        );
+    }
+
+    #[test]
+    fn test_try_from_fixed32_overflow() {
+        assert_eq!(
+            Fixed16::try_from(Fixed32::from(100., 10)),
+            Err(ConversionError::Overflow)
+        );
+        assert_eq!(
+            Fixed16::try_from(Fixed32::new(i32::MIN, 0)),
+            Err(ConversionError::Overflow)
+        );
+    }
+
+    #[test]
+    fn test_try_from_fixed32_precision_loss() {
+        assert_eq!(
+            Fixed16::try_from(Fixed32::from(0.1, 24)),
+            Err(ConversionError::PrecisionLoss)
+        );
+    }
+}
@@ -1,20 +1,70 @@
-use std::ops::{
-    Add,
-    Div,
-    Mul,
-    Sub,
+use crate::fixed::{
+    Fixed32,
+    RoundingMode,
 };
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+use std::{
+    fmt,
+    ops::{
+        Add,
+        Div,
+        Mul,
+        Sub,
+    },
+};
+#[cfg(feature = "rayon")]
+use tfhe::set_server_key;
 use tfhe::{
+    core_crypto::prelude::{
+        DynamicDistribution,
+        StandardDev,
+    },
+    integer::{
+        self,
+        IntegerCiphertext,
+        SignedRadixCiphertext,
+    },
     prelude::{
         CastInto,
+        CiphertextList,
         FheDecrypt,
+        FheEq,
+        FheOrd,
+        FheTrivialEncrypt,
         FheTryEncrypt,
+        IfThenElse,
+        RotateLeft,
+        RotateRight,
+    },
+    shortint::{
+        self,
+        PBSParameters,
+        ShortintParameterSet,
     },
     ClientKey,
+    CompressedCiphertextList,
+    CompressedCiphertextListBuilder,
+    FheBool,
     FheInt32,
+    FheInt32Id,
     FheInt64,
+    FheUint32,
+    PublicKey,
+    ServerKey,
 };
 
+// Degree-5 Chebyshev interpolant of e^y on [-1, 1], lowest power first
+const EXP_APPROX_COEFFICIENTS: [f32; 6] = [
+    1.0000446,
+    1.0000063,
+    0.49919835,
+    0.1665528,
+    0.04379233,
+    0.008635646,
+];
+
+#[derive(Clone)]
 pub struct TfheFixed32 {
     // Stores the integer representing of the fixed-point value. The
     // fixed-point representation is scaled based on the `exp` field.
@@ -50,12 +100,569 @@ impl TfheFixed32 {
         )
     }
 
+    pub fn trivial_from_fixed32(val: Fixed32) -> TfheFixed32 {
+        // Wraps a public constant without a key, for server-side
+        // `ciphertext OP constant` expressions. A trivial ciphertext hides
+        // nothing: anyone can read the value from it, so never use this for
+        // private data
+        let exp = u32::try_from(val.exp()).expect(
+            "An encrypted fixed-point number needs a non-negative exponential!",
+        );
+        TfheFixed32::new(FheInt32::encrypt_trivial(val.value()), exp)
+    }
+
+    pub fn from_fixed32_with_noise_variance(
+        ck: &ClientKey,
+        val: Fixed32,
+        variance: f64,
+    ) -> TfheFixed32 {
+        // Encrypts under the same secret key as `ck`, but with Gaussian
+        // encryption noise of the given variance, for checking how sensitive
+        // an algorithm is to noise. The variance is in TFHE's torus units
+        // (noise as a fraction of 2^64). A block with p message and carry
+        // bits decrypts correctly while |noise| < 2^-(p + 2), so with
+        // sigma = sqrt(variance) it fails with probability about
+        // erfc(2^-(p + 2) / (sigma * sqrt(2))), and the 32-bit value fails if
+        // any block does. With the default parameters (p = 4, 16 blocks),
+        // sigma = 2^-9 fails with probability around 1e-14, but sigma = 2^-7
+        // fails about half the time. The first bootstrap (any operation that
+        // propagates carries) replaces this noise with the server's own
+        let exp = u32::try_from(val.exp()).expect(
+            "An encrypted fixed-point number needs a non-negative exponential!",
+        );
+        let (integer_key, _, _, tag) = ck.clone().into_raw_parts();
+        let (glwe_secret_key, lwe_secret_key, parameters) =
+            integer_key.into_raw_parts().into_raw_parts();
+
+        let mut pbs_parameters = match parameters.pbs_parameters() {
+            Some(PBSParameters::PBS(pbs_parameters)) => pbs_parameters,
+            _ => panic!(
+                "Only support setting the noise of classic PBS parameters!"
+            ),
+        };
+        let noise = DynamicDistribution::new_gaussian_from_std_dev(
+            StandardDev(variance.sqrt()),
+        );
+        pbs_parameters.lwe_noise_distribution = noise;
+        pbs_parameters.glwe_noise_distribution = noise;
+        let noisy_key = integer::ClientKey::from_raw_parts(
+            shortint::ClientKey::from_raw_parts(
+                glwe_secret_key,
+                lwe_secret_key,
+                ShortintParameterSet::new_pbs_param_set(PBSParameters::PBS(
+                    pbs_parameters,
+                )),
+            ),
+        );
+
+        let bits_per_block = parameters.message_modulus().0.ilog2() as usize;
+        let ciphertext =
+            noisy_key.encrypt_signed_radix(val.value(), 32 / bits_per_block);
+        TfheFixed32::new(
+            FheInt32::from_raw_parts(ciphertext, FheInt32Id, tag),
+            exp,
+        )
+    }
+
+    pub fn exp(&self) -> u32 {
+        self.exp
+    }
+
+    pub fn to_fixed32(&self, client_key: &ClientKey) -> Fixed32 {
+        // Decrypts the raw value without rounding it to `f32`
+        let val_i32: i32 = self.value.decrypt(client_key);
+        Fixed32::new(val_i32, self.exp as i32)
+    }
+
     pub fn to_f32(&self, client_key: &ClientKey) -> f32 {
         let val_i32: i32 = self.value.decrypt(client_key);
         val_i32 as f32 / (1 << self.exp) as f32
     }
+
+    pub fn reciprocal(self) -> TfheFixed32 {
+        // Encrypted counterpart of `Fixed32::reciprocal` for positive values:
+        // the power-of-two initial guess comes from the encrypted position of
+        // the leading 1, followed by five Newton-Raphson iterations
+        let mut result = self.reciprocal_guess();
+        for _ in 0..5 {
+            let t1: TfheFixed32 = result.clone() * self.clone();
+            let t2: FheInt32 = -t1.value + (1 << (self.exp + 1));
+            result = result * TfheFixed32::new(t2, self.exp);
+        }
+
+        result
+    }
+
+    pub(crate) fn reciprocal_guess(&self) -> TfheFixed32 {
+        // 2^-k for a positive self = m * 2^k with m in [1, 2), which is
+        // between 1 / self and 2 / self
+        let leading_one_index: FheUint32 = self.value.ilog2();
+        let shift: FheUint32 = -leading_one_index + self.exp * 2;
+        let guess: FheInt32 = FheInt32::encrypt_trivial(1i32) << shift;
+        TfheFixed32::new(guess, self.exp)
+    }
+
+    pub fn rsqrt(self) -> TfheFixed32 {
+        // 1 / sqrt(x) for positive values without any division, by Newton's
+        // iteration y <- y * (3 - x * y^2) / 2. As in `reciprocal`, the
+        // initial power-of-two guess comes from the encrypted position of the
+        // leading 1 and is within a factor of sqrt(2) of the result. Requires
+        // exp <= 28 so that 3 fits
+        let leading_one_index: FheUint32 = self.value.ilog2();
+        let shift: FheUint32 = (-leading_one_index + self.exp * 3) >> 1u32;
+        let guess: FheInt32 = FheInt32::encrypt_trivial(1i32) << shift;
+        let half = Fixed32::from(0.5, self.exp as i32);
+
+        let mut result = TfheFixed32::new(guess, self.exp);
+        for _ in 0..5 {
+            let t1: TfheFixed32 =
+                result.clone() * result.clone() * self.clone();
+            let t2: FheInt32 = -t1.value + (3 << self.exp);
+            result =
+                (result * TfheFixed32::new(t2, self.exp)).mul_plaintext(half);
+        }
+
+        result
+    }
+
+    pub fn mul_plaintext(self, scalar: Fixed32) -> TfheFixed32 {
+        // Multiplies by a public constant without encrypting it
+        let scalar = scalar
+            .to_fixed32_at_exp(self.exp as i32, RoundingMode::Floor)
+            .value() as i64;
+        let lhs_val_i64: FheInt64 = self.value.cast_into();
+        let product_i64: FheInt64 = (lhs_val_i64 * scalar) >> self.exp;
+        let product_i32: FheInt32 = product_i64.cast_into();
+
+        TfheFixed32::new(product_i32, self.exp)
+    }
+
+    pub fn add_plaintext(self, scalar: Fixed32) -> TfheFixed32 {
+        let scalar = scalar
+            .to_fixed32_at_exp(self.exp as i32, RoundingMode::Floor)
+            .value();
+        TfheFixed32::new(self.value + scalar, self.exp)
+    }
+
+    pub fn exp_approx(self) -> TfheFixed32 {
+        // Approximates e^x as p(x / 4)^4, where p is a degree-5 polynomial
+        // for e^y on [-1, 1]. Valid for x in [-4, 4]: at exp = 24 the absolute
+        // error stays below 0.005 (relative error below 0.05%), and it grows
+        // quickly outside that range. Requires exp <= 25 so that e^4 fits.
+        let exp = self.exp as i32;
+        let coefficients =
+            EXP_APPROX_COEFFICIENTS.map(|c| Fixed32::from(c, exp));
+        let y = TfheFixed32::new(self.value >> 2u32, self.exp);
+
+        // Horner's method, 5 multiplications
+        let mut result = y
+            .clone()
+            .mul_plaintext(coefficients[5])
+            .add_plaintext(coefficients[4]);
+        for &coefficient in coefficients[..4].iter().rev() {
+            result = (result * y.clone()).add_plaintext(coefficient);
+        }
+
+        let squared = result.clone() * result;
+        squared.clone() * squared
+    }
+
+    pub fn encrypt_slice(
+        client_key: &ClientKey,
+        values: &[f32],
+        exp: u32,
+    ) -> Vec<TfheFixed32> {
+        // Every encryption is independent and only needs the client key, so
+        // Rayon worker threads do not need a server key installed. Computing
+        // on the results in parallel does: TFHE keeps the server key in a
+        // thread local, so call `set_server_key` on every worker first (e.g.
+        // via `rayon::broadcast`).
+        #[cfg(feature = "rayon")]
+        let values = values.par_iter();
+        #[cfg(not(feature = "rayon"))]
+        let values = values.iter();
+
+        values
+            .map(|&value| TfheFixed32::from(client_key, value, exp))
+            .collect()
+    }
+
+    pub fn public_encrypt_slice(
+        public_key: &PublicKey,
+        values: &[f32],
+        exp: u32,
+    ) -> Vec<TfheFixed32> {
+        // Lets a data owner encrypt without holding the client key; only the
+        // holder of the matching client key can decrypt the results
+        #[cfg(feature = "rayon")]
+        let values = values.par_iter();
+        #[cfg(not(feature = "rayon"))]
+        let values = values.iter();
+
+        values
+            .map(|&value| {
+                let val_i32 = (value * (1 << exp) as f32).round() as i32;
+                TfheFixed32::new(
+                    FheInt32::try_encrypt(val_i32, public_key).unwrap(),
+                    exp,
+                )
+            })
+            .collect()
+    }
+
+    pub fn decrypt_slice(
+        client_key: &ClientKey,
+        values: &[TfheFixed32],
+    ) -> Vec<f32> {
+        values
+            .iter()
+            .map(|value| value.to_f32(client_key))
+            .collect()
+    }
+
+    pub fn add_many(
+        values: Vec<TfheFixed32>,
+        server_key: &ServerKey,
+    ) -> TfheFixed32 {
+        // Sums in a balanced binary tree, so each input passes through
+        // O(log N) additions instead of O(N). With the `rayon` feature the two
+        // halves are summed in parallel, and `server_key`, which must be the
+        // key set on the calling thread, is installed on the worker threads
+        if values.is_empty() {
+            panic!("Cannot sum an empty list of encrypted values!")
+        }
+
+        add_tree(values, server_key)
+    }
+
+    pub fn encrypt_bit_vector(
+        client_key: &ClientKey,
+        bits: [bool; 32],
+        exp: u32,
+    ) -> TfheFixed32 {
+        // Encrypts every bit separately (index 0 is the LSB) and assembles
+        // the integer homomorphically, so the bits can come from bit-level
+        // FHE programs
+        let value = bits.iter().enumerate().fold(
+            FheInt32::encrypt_trivial(0i32),
+            |acc, (i, &bit)| {
+                let bit: FheInt32 =
+                    FheBool::try_encrypt(bit, client_key).unwrap().cast_into();
+                acc | (bit << i as u32)
+            },
+        );
+
+        TfheFixed32::new(value, exp)
+    }
+
+    pub fn decrypt_bit_vector(&self, client_key: &ClientKey) -> [bool; 32] {
+        let value: i32 = self.value.decrypt(client_key);
+        Fixed32::new(value, self.exp as i32).to_bit_vector()
+    }
+
+    pub fn bit_and_encrypted(self, other: TfheFixed32) -> TfheFixed32 {
+        // Bitwise operations act on the raw scaled integer, not on the
+        // mathematical value
+        self.check_same_exp(&other);
+        TfheFixed32::new(self.value & other.value, self.exp)
+    }
+
+    pub fn bit_or_encrypted(self, other: TfheFixed32) -> TfheFixed32 {
+        self.check_same_exp(&other);
+        TfheFixed32::new(self.value | other.value, self.exp)
+    }
+
+    pub fn bit_xor_encrypted(self, other: TfheFixed32) -> TfheFixed32 {
+        self.check_same_exp(&other);
+        TfheFixed32::new(self.value ^ other.value, self.exp)
+    }
+
+    pub fn bit_not_encrypted(self) -> TfheFixed32 {
+        TfheFixed32::new(!self.value, self.exp)
+    }
+
+    pub fn shift_left_encrypted(self, n: u32) -> TfheFixed32 {
+        // Multiplies by 2^n while keeping `exp`. Unlike `Fixed32::shift_left`
+        // an overflow cannot be detected, so the bits shifted out are lost
+        // and shifts of 32 or more give zero
+        if n >= 32 {
+            return TfheFixed32::new(FheInt32::encrypt_trivial(0i32), self.exp);
+        }
+        TfheFixed32::new(self.value << n, self.exp)
+    }
+
+    pub fn shift_right_encrypted(self, n: u32) -> TfheFixed32 {
+        // Halves the value n times while keeping `exp`, rounding toward
+        // negative infinity like `Fixed32::shift_right`
+        TfheFixed32::new(self.value >> n.min(31), self.exp)
+    }
+
+    pub fn rotate_left_encrypted(self, n: u32) -> TfheFixed32 {
+        // Rotates the 32-bit two's complement pattern; `n` is taken modulo 32.
+        // The bits wrapping around from the top land in the fractional part,
+        // so the result is not a simple multiple of the value
+        TfheFixed32::new(self.value.rotate_left(n % 32), self.exp)
+    }
+
+    pub fn rotate_right_encrypted(self, n: u32) -> TfheFixed32 {
+        // The low fractional bits wrap around into the sign and integer bits
+        TfheFixed32::new(self.value.rotate_right(n % 32), self.exp)
+    }
+
+    pub fn negate_if_encrypted(self, cond: FheBool) -> TfheFixed32 {
+        // Evaluates both branches and selects one homomorphically, so the
+        // server never learns `cond`
+        let negated = -&self.value;
+        TfheFixed32::new(cond.if_then_else(&negated, &self.value), self.exp)
+    }
+
+    pub fn ge_plaintext(&self, scalar: Fixed32) -> FheBool {
+        // Encrypted `self >= scalar` against a public constant
+        let scalar = scalar
+            .to_fixed32_at_exp(self.exp as i32, RoundingMode::Floor)
+            .value();
+        self.value.ge(scalar)
+    }
+
+    pub fn conditional_select(
+        cond: &FheBool,
+        then: &TfheFixed32,
+        otherwise: &TfheFixed32,
+    ) -> TfheFixed32 {
+        // Homomorphic `if cond { then } else { otherwise }`
+        then.check_same_exp(otherwise);
+        TfheFixed32::new(
+            cond.if_then_else(&then.value, &otherwise.value),
+            then.exp,
+        )
+    }
+
+    pub fn homomorphic_if_then_else(
+        cond: FheBool,
+        then_branch: impl FnOnce() -> TfheFixed32,
+        else_branch: impl FnOnce() -> TfheFixed32,
+    ) -> TfheFixed32 {
+        // Both branches are always computed, whatever `cond` decrypts to:
+        // the server cannot branch on a ciphertext, and skipping one branch
+        // would leak the condition through the running time. The cost is
+        // therefore the sum of both branches
+        let then = then_branch();
+        let otherwise = else_branch();
+        TfheFixed32::conditional_select(&cond, &then, &otherwise)
+    }
+
+    pub fn rescale(self, new_exp: u32) -> TfheFixed32 {
+        // Lowering the exponent drops fractional bits (rounding toward
+        // negative infinity); raising it shifts left, which overflows if the
+        // integer part no longer fits
+        let value = if new_exp < self.exp {
+            self.value >> (self.exp - new_exp)
+        } else {
+            self.value << (new_exp - self.exp)
+        };
+
+        TfheFixed32::new(value, new_exp)
+    }
+
+    pub fn apply_blockwise_via_pbs(
+        self,
+        f: impl Fn(u64) -> u64,
+        server_key: &integer::ServerKey,
+    ) -> TfheFixed32 {
+        // Not a function of the whole value: `f` is a digit map, applied to
+        // each radix block separately with one programmable bootstrap. A
+        // lookup table only covers the message bits of one block (two with
+        // the default parameters), and one over all 2^32 values of an
+        // `FheInt32` cannot be built, so `f` maps a digit in
+        // [0, message_modulus) to a new digit, reduced modulo message_modulus.
+        // The blocks are independent, so this spends no multiplicative depth
+        let shortint_key: &shortint::ServerKey = server_key.as_ref();
+        let modulus = server_key.message_modulus().0 as u64;
+        // The table also spans the carry bits, which are empty between
+        // high-level operations, so `f` only ever sees the message bits
+        let lookup_table = shortint_key
+            .generate_lookup_table(|digit| f(digit % modulus) % modulus);
+
+        let (radix, id, tag) = self.value.into_raw_parts();
+        #[cfg(feature = "rayon")]
+        let blocks = radix.blocks().par_iter();
+        #[cfg(not(feature = "rayon"))]
+        let blocks = radix.blocks().iter();
+        let blocks = blocks
+            .map(|block| shortint_key.apply_lookup_table(block, &lookup_table))
+            .collect();
+
+        TfheFixed32::new(
+            FheInt32::from_raw_parts(
+                SignedRadixCiphertext::from_blocks(blocks),
+                id,
+                tag,
+            ),
+            self.exp,
+        )
+    }
+
+    pub fn bootstrap(self, server_key: &integer::ServerKey) -> TfheFixed32 {
+        // Refreshes the noise of every block with an identity programmable
+        // bootstrap, one PBS per block. Integer operations already bootstrap
+        // the blocks they propagate carries through; this is an explicit
+        // refresh point for circuits budgeted with `TfheFixed32Traced`
+        self.apply_blockwise_via_pbs(|digit| digit, server_key)
+    }
+
+    fn sign_mask(&self, server_key: &integer::ServerKey) -> FheInt32 {
+        // -1 for negative values and 0 otherwise, in one bootstrap: every
+        // block is replaced by the most significant one, and a lookup table
+        // then spreads its top bit over all of the block's bits
+        let modulus = server_key.message_modulus().0 as u64;
+        let (radix, id, tag) = self.value.clone().into_raw_parts();
+        let top_block = radix.blocks().last().unwrap().clone();
+        let spread =
+            SignedRadixCiphertext::from_blocks(vec![
+                top_block;
+                radix.blocks().len()
+            ]);
+
+        TfheFixed32::new(FheInt32::from_raw_parts(spread, id, tag), self.exp)
+            .apply_blockwise_via_pbs(
+                |digit| if digit >= modulus / 2 { modulus - 1 } else { 0 },
+                server_key,
+            )
+            .value
+    }
+
+    // The functions below are built on `sign_mask`, followed by bitwise
+    // operations that TFHE-rs also evaluates block by block, so none of them
+    // spends multiplicative depth. `server_key` is only used for the lookup
+    // table; the high-level server key must still be set
+    pub fn abs(self, server_key: &integer::ServerKey) -> TfheFixed32 {
+        // (x ^ mask) - mask negates exactly the negative values
+        let mask = self.sign_mask(server_key);
+        TfheFixed32::new((self.value ^ &mask) - mask, self.exp)
+    }
+
+    pub fn sign(self, server_key: &integer::ServerKey) -> TfheFixed32 {
+        // 1, -1 or 0 at the same exponent, like `Fixed32::signum_fixed`
+        let mask = self.sign_mask(server_key);
+        let nonzero: FheInt32 = self.value.ne(0).cast_into();
+        TfheFixed32::new((mask | nonzero) << self.exp, self.exp)
+    }
+
+    pub fn relu(self, server_key: &integer::ServerKey) -> TfheFixed32 {
+        // max(self, 0), without the polynomial approximation a
+        // multiplication-only scheme would need
+        let mask = self.sign_mask(server_key);
+        TfheFixed32::new(self.value & !mask, self.exp)
+    }
+
+    pub fn mul_lazy(self, rhs: TfheFixed32) -> TfheFixed32Lazy {
+        // Multiplies without the `>> exp` rescale; see `TfheFixed32Lazy`
+        self.check_same_exp(&rhs);
+        let lhs_val_i64: FheInt64 = self.value.cast_into();
+        let rhs_val_i64: FheInt64 = rhs.value.cast_into();
+
+        TfheFixed32Lazy {
+            value: lhs_val_i64 * rhs_val_i64,
+            exp: self.exp,
+        }
+    }
+
+    pub fn to_compressed_bytes(&self) -> Result<Vec<u8>, SerializationError> {
+        // Serializes the ciphertext in TFHE's compressed list format, which
+        // is several times smaller than the regular format. Unlike
+        // `CompressedFheInt32`, which can only be produced at encryption
+        // time, this also works for computation results. The server key must
+        // be generated with `ConfigBuilder::enable_compression`. The
+        // exponent is not included
+        let mut builder = CompressedCiphertextListBuilder::new();
+        builder.push(self.value.clone());
+        let compressed = builder
+            .build()
+            .map_err(|_| SerializationError::Compression)?;
+
+        bincode::serialize(&compressed)
+            .map_err(|_| SerializationError::Serialization)
+    }
+
+    pub fn from_compressed_bytes(
+        bytes: &[u8],
+        exp: u32,
+    ) -> Result<TfheFixed32, DeserializationError> {
+        // Decompressing needs the server key to be set
+        let compressed: CompressedCiphertextList = bincode::deserialize(bytes)
+            .map_err(|_| DeserializationError::InvalidBytes)?;
+        let value: FheInt32 = compressed
+            .get(0)
+            .map_err(|_| DeserializationError::Decompression)?
+            .ok_or(DeserializationError::MissingCiphertext)?;
+
+        Ok(TfheFixed32::new(value, exp))
+    }
+
+    fn check_same_exp(&self, other: &TfheFixed32) {
+        if self.exp != other.exp {
+            panic!(
+                "Only support operations between two fixed-point numbers \
+            with the same exponential!"
+            )
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SerializationError {
+    // The server key was generated without compression enabled
+    Compression,
+
+    // The compressed ciphertext list could not be serialized
+    Serialization,
+}
+
+impl fmt::Display for SerializationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SerializationError::Compression => {
+                write!(f, "the server key does not support compression")
+            }
+            SerializationError::Serialization => {
+                write!(f, "failed to serialize compressed ciphertext")
+            }
+        }
+    }
 }
 
+impl std::error::Error for SerializationError {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeserializationError {
+    // The bytes are not a serialized compressed ciphertext list
+    InvalidBytes,
+
+    // The compressed list does not contain a ciphertext
+    MissingCiphertext,
+
+    // The ciphertext could not be decompressed into an `FheInt32`
+    Decompression,
+}
+
+impl fmt::Display for DeserializationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DeserializationError::InvalidBytes => {
+                write!(f, "invalid compressed ciphertext bytes")
+            }
+            DeserializationError::MissingCiphertext => {
+                write!(f, "compressed ciphertext list is empty")
+            }
+            DeserializationError::Decompression => {
+                write!(f, "failed to decompress ciphertext")
+            }
+        }
+    }
+}
+
+impl std::error::Error for DeserializationError {}
+
 impl Add for TfheFixed32 {
     type Output = Self;
 
@@ -104,63 +711,761 @@ impl Mul for TfheFixed32 {
 impl Div for TfheFixed32 {
     type Output = Self;
 
+    #[allow(clippy::suspicious_arithmetic_impl)]
     fn div(self, other: Self) -> Self::Output {
-        let quotient = self.value / other.value * (1 << self.exp);
-        Self::new(quotient, self.exp)
+        // Dividing the raw integers would drop the fractional bits, and
+        // encrypted long division is too costly; use the Newton-Raphson
+        // reciprocal instead, which inherits its accuracy and only supports
+        // positive divisors
+        self * other.reciprocal()
+    }
+}
+
+#[cfg_attr(not(feature = "rayon"), allow(clippy::only_used_in_recursion))]
+fn add_tree(
+    mut values: Vec<TfheFixed32>,
+    server_key: &ServerKey,
+) -> TfheFixed32 {
+    if values.len() == 1 {
+        return values.pop().unwrap();
+    }
+
+    let right = values.split_off(values.len() / 2);
+    // Either half may run on a worker thread, which has no server key yet
+    #[cfg(feature = "rayon")]
+    let (left, right) = rayon::join(
+        || {
+            set_server_key(server_key.clone());
+            add_tree(values, server_key)
+        },
+        || {
+            set_server_key(server_key.clone());
+            add_tree(right, server_key)
+        },
+    );
+    #[cfg(not(feature = "rayon"))]
+    let (left, right) =
+        (add_tree(values, server_key), add_tree(right, server_key));
+
+    left + right
+}
+
+#[derive(Clone)]
+pub struct TfheFixed32Lazy {
+    // A product of two `TfheFixed32` values that has not been shifted back
+    // yet, so it carries `2 * exp` fractional bits
+    value: FheInt64,
+
+    // The exponent of the operands and of the rescaled result
+    exp: u32,
+}
+
+impl TfheFixed32Lazy {
+    pub fn exp(&self) -> u32 {
+        self.exp
+    }
+
+    pub fn rescale(self) -> TfheFixed32 {
+        // Applies the `>> exp` step that `TfheFixed32::mul` performs after
+        // every product, once for the whole chain
+        let value_i32: FheInt32 = (self.value >> self.exp).cast_into();
+        TfheFixed32::new(value_i32, self.exp)
+    }
+
+    fn check_same_exp(&self, exp: u32) {
+        if self.exp != exp {
+            panic!(
+                "Only support operations between two fixed-point numbers \
+            with the same exponential!"
+            )
+        }
+    }
+
+    fn lift(&self, other: TfheFixed32) -> FheInt64 {
+        // Brings an ordinary value to the `2 * exp` scale of the product
+        self.check_same_exp(other.exp);
+        let other_i64: FheInt64 = other.value.cast_into();
+        other_i64 << self.exp
+    }
+}
+
+impl Add for TfheFixed32Lazy {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self::Output {
+        self.check_same_exp(other.exp);
+        Self {
+            value: self.value + other.value,
+            exp: self.exp,
+        }
+    }
+}
+
+impl Sub for TfheFixed32Lazy {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self::Output {
+        self.check_same_exp(other.exp);
+        Self {
+            value: self.value - other.value,
+            exp: self.exp,
+        }
+    }
+}
+
+impl Add<TfheFixed32> for TfheFixed32Lazy {
+    type Output = Self;
+
+    fn add(self, other: TfheFixed32) -> Self::Output {
+        let other = self.lift(other);
+        Self {
+            value: self.value + other,
+            exp: self.exp,
+        }
+    }
+}
+
+impl Sub<TfheFixed32> for TfheFixed32Lazy {
+    type Output = Self;
+
+    fn sub(self, other: TfheFixed32) -> Self::Output {
+        let other = self.lift(other);
+        Self {
+            value: self.value - other,
+            exp: self.exp,
+        }
+    }
+}
+
+pub mod key_management {
+    use tfhe::{
+        generate_keys,
+        set_server_key,
+        ClientKey,
+        ConfigBuilder,
+        ServerKey,
+    };
+
+    pub struct FheContext {
+        // Encrypts and decrypts; stays with the data owner
+        client_key: ClientKey,
+
+        // Evaluates operations; already installed on the creating thread
+        server_key: ServerKey,
+    }
+
+    impl FheContext {
+        pub fn new() -> Self {
+            // Generates keys with the default parameters and installs the
+            // server key on the current thread. Other threads still need
+            // `set_server_key(context.server_key().clone())`
+            let config = ConfigBuilder::default().build();
+            let (client_key, server_key) = generate_keys(config);
+            set_server_key(server_key.clone());
+
+            Self {
+                client_key,
+                server_key,
+            }
+        }
+
+        pub fn client_key(&self) -> &ClientKey {
+            &self.client_key
+        }
+
+        pub fn server_key(&self) -> &ServerKey {
+            &self.server_key
+        }
+    }
+
+    impl Default for FheContext {
+        fn default() -> Self {
+            Self::new()
+        }
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::*;
+    use super::{
+        key_management::FheContext,
+        *,
+    };
+    use crate::test_utils::{
+        test_assert_encrypted_eq,
+        test_assert_encrypted_raw_eq,
+    };
     use tfhe::{
         generate_keys,
+        prelude::FheEncrypt,
         set_server_key,
         ConfigBuilder,
     };
 
     #[test]
     fn test_add() {
-        let config = ConfigBuilder::default().build();
-        let (client_key, server_key) = generate_keys(config);
-
-        set_server_key(server_key);
-        let a = TfheFixed32::new_with_key(&client_key, 10, 24);
-        let b = TfheFixed32::new_with_key(&client_key, 15, 24);
+        let context = FheContext::new();
+        let client_key = context.client_key();
+        let a = TfheFixed32::new_with_key(client_key, 10, 24);
+        let b = TfheFixed32::new_with_key(client_key, 15, 24);
         let result = a + b;
-        let result_val: i32 = result.value.decrypt(&client_key);
 
-        assert_eq!(result_val, 25);
+        test_assert_encrypted_eq(
+            client_key,
+            &result,
+            25. / (1 << 24) as f32,
+            0,
+        );
         assert_eq!(result.exp, 24);
     }
 
     #[test]
     fn test_sub() {
-        let config = ConfigBuilder::default().build();
-        let (client_key, server_key) = generate_keys(config);
-
-        set_server_key(server_key);
-        let a = TfheFixed32::new_with_key(&client_key, 15, 24);
-        let b = TfheFixed32::new_with_key(&client_key, 10, 24);
+        let context = FheContext::new();
+        let client_key = context.client_key();
+        let a = TfheFixed32::new_with_key(client_key, 15, 24);
+        let b = TfheFixed32::new_with_key(client_key, 10, 24);
         let result = a - b;
-        let result_val: i32 = result.value.decrypt(&client_key);
 
-        assert_eq!(result_val, 5);
+        test_assert_encrypted_eq(client_key, &result, 5. / (1 << 24) as f32, 0);
         assert_eq!(result.exp, 24);
     }
 
     #[test]
     fn test_mul() {
+        let context = FheContext::new();
+        let client_key = context.client_key();
+        let a = TfheFixed32::from(client_key, 2.47, 24);
+        let b = TfheFixed32::from(client_key, 3.19, 24);
+        let result = a * b;
+
+        test_assert_encrypted_eq(client_key, &result, 7.8793, 8);
+        assert_eq!(result.exp, 24);
+    }
+
+    #[test]
+    fn test_bitwise_mask() {
+        let context = FheContext::new();
+        let client_key = context.client_key();
+        let integer_mask = !((1 << 8) - 1);
+        let a = TfheFixed32::from(client_key, 3.75, 8);
+        let mask = TfheFixed32::new_with_key(client_key, integer_mask, 8);
+        let result = a.clone().bit_and_encrypted(mask);
+        test_assert_encrypted_eq(client_key, &result, 3., 0);
+
+        let b = TfheFixed32::new_with_key(client_key, 0b0011, 8);
+        let c = TfheFixed32::new_with_key(client_key, 0b0101, 8);
+        let or = b.clone().bit_or_encrypted(c.clone());
+        test_assert_encrypted_raw_eq(client_key, &or, 0b0111, 0);
+        let xor = b.bit_xor_encrypted(c);
+        test_assert_encrypted_raw_eq(client_key, &xor, 0b0110, 0);
+        let not = a.bit_not_encrypted();
+        test_assert_encrypted_raw_eq(client_key, &not, !(375 * 256 / 100), 0);
+    }
+
+    #[test]
+    fn test_encrypt_decrypt_slice() {
         let config = ConfigBuilder::default().build();
+        let (client_key, _) = generate_keys(config);
+
+        let values = [1.5, -0.25, 3.75, 0.];
+        let encrypted = TfheFixed32::encrypt_slice(&client_key, &values, 16);
+        assert_eq!(encrypted.len(), values.len());
+        assert!(encrypted.iter().all(|x| x.exp == 16));
+        assert_eq!(TfheFixed32::decrypt_slice(&client_key, &encrypted), values);
+    }
+
+    #[test]
+    fn test_public_encrypt_slice() {
+        let context = FheContext::new();
+        let client_key = context.client_key();
+        let public_key = PublicKey::new(client_key);
+
+        let values = [1.5, -0.25, 3.75, 0.];
+        let encrypted =
+            TfheFixed32::public_encrypt_slice(&public_key, &values, 16);
+        assert!(encrypted.iter().all(|x| x.exp == 16));
+        assert_eq!(TfheFixed32::decrypt_slice(client_key, &encrypted), values);
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    #[ignore]
+    fn bench_encrypt_slice_parallel() {
+        // Run with `cargo test --release --features rayon -- --ignored`
+        use crate::measure::measure_duration;
+
+        let config = ConfigBuilder::default().build();
+        let (client_key, _) = generate_keys(config);
+        let values: Vec<f32> = (0..100).map(|i| i as f32 / 10.).collect();
+
+        let (_, sequential_time) = measure_duration(|| {
+            values
+                .iter()
+                .map(|&value| TfheFixed32::from(&client_key, value, 16))
+                .collect::<Vec<_>>()
+        });
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(4)
+            .build()
+            .unwrap();
+        let (encrypted, parallel_time) = measure_duration(|| {
+            pool.install(|| {
+                TfheFixed32::encrypt_slice(&client_key, &values, 16)
+            })
+        });
+
+        assert_eq!(TfheFixed32::decrypt_slice(&client_key, &encrypted), values);
+        println!(
+            "sequential: {:.3?}, parallel (4 threads): {:.3?}, speedup: {:.2}x",
+            sequential_time,
+            parallel_time,
+            sequential_time.as_secs_f64() / parallel_time.as_secs_f64()
+        );
+    }
+
+    #[test]
+    fn test_mul_add_plaintext() {
+        let context = FheContext::new();
+        let client_key = context.client_key();
+        let a = TfheFixed32::from(client_key, 2.5, 16);
+        let result = a
+            .mul_plaintext(Fixed32::from(-1.5, 16))
+            .add_plaintext(Fixed32::from(0.25, 4));
+        test_assert_encrypted_eq(client_key, &result, -3.5, 0);
+    }
+
+    #[test]
+    fn test_trivial_from_fixed32() {
+        let context = FheContext::new();
+        let client_key = context.client_key();
+        let encrypted = TfheFixed32::from(client_key, 1.5, 16);
+        let trivial_one =
+            TfheFixed32::trivial_from_fixed32(Fixed32::from(1., 16));
+        assert_eq!(trivial_one.exp(), 16);
+        test_assert_encrypted_eq(
+            client_key,
+            &(encrypted + trivial_one),
+            2.5,
+            0,
+        );
+    }
+
+    #[test]
+    fn test_from_fixed32_with_noise_variance() {
+        let context = FheContext::new();
+        let client_key = context.client_key();
+        let val = Fixed32::from(-3.15, 16);
+        let x = TfheFixed32::from_fixed32_with_noise_variance(
+            client_key,
+            val,
+            2f64.powi(-80),
+        );
+        assert_eq!(x.exp(), 16);
+        assert_eq!(x.to_fixed32(client_key), val);
+
+        let y = x + TfheFixed32::from(client_key, 1., 16);
+        test_assert_encrypted_eq(client_key, &y, val.to_f32() + 1., 0);
+    }
+
+    #[test]
+    fn test_exp_approx() {
+        let context = FheContext::new();
+        let client_key = context.client_key();
+        // 2^14 and 5 * 2^14 ULPs are about 0.001 and 0.005 at exponent 24
+        let x = TfheFixed32::from(client_key, 1., 24);
+        test_assert_encrypted_eq(
+            client_key,
+            &x.exp_approx(),
+            std::f32::consts::E,
+            1 << 14,
+        );
+
+        for x in [-4., -2.5, 0., 0.5, 3., 4.] {
+            let encrypted = TfheFixed32::from(client_key, x, 24);
+            test_assert_encrypted_eq(
+                client_key,
+                &encrypted.exp_approx(),
+                f32::exp(x),
+                5 << 14,
+            );
+        }
+    }
+
+    #[test]
+    fn test_div() {
+        let context = FheContext::new();
+        let client_key = context.client_key();
+        for (a, b) in [(10., 3.), (0.5, 0.1), (-7.5, 2.5), (1., 107.4)] {
+            let quotient = TfheFixed32::from(client_key, a, 24)
+                / TfheFixed32::from(client_key, b, 24);
+            let result = quotient.to_fixed32(client_key);
+            assert!(
+                Fixed32::from(a / b, 24).approx_eq_within_percent(result, 5.),
+                "{} / {} gave {}",
+                a,
+                b,
+                result.to_f32()
+            );
+        }
+    }
+
+    #[test]
+    fn test_rsqrt() {
+        let context = FheContext::new();
+        let client_key = context.client_key();
+        for x in [0.01, 0.25, 2., 9., 1000.] {
+            // Compare against the input as rounded to 16 fractional bits
+            let rounded = Fixed32::from(x, 16).to_f32();
+            let result = TfheFixed32::from(client_key, x, 16).rsqrt();
+            test_assert_encrypted_eq(
+                client_key,
+                &result,
+                1. / rounded.sqrt(),
+                8,
+            );
+        }
+    }
+
+    #[test]
+    fn test_reciprocal() {
+        let context = FheContext::new();
+        let client_key = context.client_key();
+        for divisor in [0.22, 3.15, 107.4] {
+            let x = TfheFixed32::from(client_key, divisor, 24);
+            let result = x.reciprocal();
+            let expected = Fixed32::from(divisor, 24).reciprocal();
+            assert_eq!(result.to_fixed32(client_key), expected);
+            test_assert_encrypted_eq(
+                client_key,
+                &result,
+                1. / divisor,
+                1 << 14,
+            );
+        }
+    }
+
+    #[test]
+    fn test_negate_if_encrypted() {
+        let context = FheContext::new();
+        let client_key = context.client_key();
+        let x = TfheFixed32::from(client_key, 5., 24);
+        let yes = FheBool::encrypt(true, client_key);
+        let no = FheBool::encrypt(false, client_key);
+        test_assert_encrypted_eq(
+            client_key,
+            &x.clone().negate_if_encrypted(yes),
+            -5.,
+            0,
+        );
+        test_assert_encrypted_eq(client_key, &x.negate_if_encrypted(no), 5., 0);
+    }
+
+    #[test]
+    fn test_mul_lazy() {
+        let context = FheContext::new();
+        let client_key = context.client_key();
+        let a = TfheFixed32::from(client_key, 1.5, 24);
+        let b = TfheFixed32::from(client_key, -2.25, 24);
+        let c = TfheFixed32::from(client_key, 0.125, 24);
+
+        // (a * b) + c rescales once and gives the same bits as the eager form
+        let eager = (a.clone() * b.clone() + c.clone()).to_f32(client_key);
+        let lazy = (a.clone().mul_lazy(b.clone()) + c).rescale();
+        test_assert_encrypted_eq(client_key, &lazy, eager, 0);
+        assert_eq!(eager, -3.25);
+
+        let difference =
+            (a.clone().mul_lazy(a.clone()) - b.clone().mul_lazy(b)).rescale();
+        test_assert_encrypted_eq(
+            client_key,
+            &difference,
+            1.5 * 1.5 - 2.25 * 2.25,
+            0,
+        );
+    }
+
+    #[test]
+    #[ignore]
+    fn bench_mul_lazy_dot_product() {
+        // Run with `cargo test --release -- --ignored`. The eager dot product
+        // rescales after every product, the lazy one only once at the end
+        use crate::measure::measure_duration;
+
+        let context = FheContext::new();
+        let client_key = context.client_key();
+        let xs: Vec<f32> = (0..8).map(|i| 0.1 * i as f32 - 0.3).collect();
+        let a = TfheFixed32::encrypt_slice(client_key, &xs, 24);
+        let b = TfheFixed32::encrypt_slice(client_key, &xs, 24);
+
+        let (eager, eager_time) = measure_duration(|| {
+            a.iter()
+                .zip(&b)
+                .map(|(x, y)| x.clone() * y.clone())
+                .reduce(|acc, p| acc + p)
+                .unwrap()
+        });
+        let (lazy, lazy_time) = measure_duration(|| {
+            a.iter()
+                .zip(&b)
+                .map(|(x, y)| x.clone().mul_lazy(y.clone()))
+                .reduce(|acc, p| acc + p)
+                .unwrap()
+                .rescale()
+        });
+
+        let exact: f32 = xs.iter().map(|x| x * x).sum();
+        println!(
+            "eager: {:.3?}, 8 rescales, error {:e}; lazy: {:.3?}, 1 rescale, error {:e}",
+            eager_time,
+            (eager.to_f32(client_key) - exact).abs(),
+            lazy_time,
+            (lazy.to_f32(client_key) - exact).abs()
+        );
+    }
+
+    #[test]
+    fn test_compressed_bytes_round_trip() {
+        use tfhe::shortint::parameters::COMP_PARAM_MESSAGE_2_CARRY_2_KS_PBS_TUNIFORM_2M64;
+
+        let config = ConfigBuilder::default()
+            .enable_compression(
+                COMP_PARAM_MESSAGE_2_CARRY_2_KS_PBS_TUNIFORM_2M64,
+            )
+            .build();
         let (client_key, server_key) = generate_keys(config);
 
         set_server_key(server_key);
-        let a = TfheFixed32::from(&client_key, 2.47, 24);
-        let b = TfheFixed32::from(&client_key, 3.19, 24);
-        let result = a * b;
-        let result_val = result.to_f32(&client_key);
+        let x = TfheFixed32::from(&client_key, -3.15, 24);
+        let regular = bincode::serialize(&x.value).unwrap();
+        let compressed = x.to_compressed_bytes().unwrap();
+        println!(
+            "regular: {} bytes, compressed: {} bytes",
+            regular.len(),
+            compressed.len()
+        );
 
-        assert_eq!(result_val, 7.8793);
-        assert_eq!(result.exp, 24);
+        let y = TfheFixed32::from_compressed_bytes(&compressed, 24).unwrap();
+        assert_eq!(y.to_f32(&client_key), x.to_f32(&client_key));
+        assert!(compressed.len() < regular.len());
+        assert_eq!(
+            TfheFixed32::from_compressed_bytes(&compressed[1..], 24).err(),
+            Some(DeserializationError::InvalidBytes)
+        );
+    }
+
+    #[test]
+    fn test_rescale() {
+        let context = FheContext::new();
+        let client_key = context.client_key();
+        let x = TfheFixed32::from(client_key, -3.15, 24);
+        let lower = x.clone().rescale(8);
+        assert_eq!(lower.exp, 8);
+        let expected = Fixed32::from(-3.15, 24)
+            .to_fixed32_at_exp(8, RoundingMode::Floor)
+            .to_f32();
+        test_assert_encrypted_eq(client_key, &lower, expected, 0);
+
+        let higher = TfheFixed32::from(client_key, 2.75, 8).rescale(20);
+        assert_eq!(higher.exp, 20);
+        test_assert_encrypted_eq(client_key, &higher, 2.75, 0);
+        assert_eq!(
+            x.clone().rescale(24).to_f32(client_key),
+            x.to_f32(client_key)
+        );
+    }
+
+    #[test]
+    fn test_conditional_select() {
+        let context = FheContext::new();
+        let client_key = context.client_key();
+        let x = TfheFixed32::from(client_key, 1.5, 24);
+        let y = TfheFixed32::from(client_key, -2., 24);
+        let cond = x.ge_plaintext(Fixed32::from(1., 16));
+        let result = TfheFixed32::conditional_select(&cond, &x, &y);
+        test_assert_encrypted_eq(client_key, &result, 1.5, 0);
+
+        let cond = x.ge_plaintext(Fixed32::from(1.75, 24));
+        let result = TfheFixed32::conditional_select(&cond, &x, &y);
+        test_assert_encrypted_eq(client_key, &result, -2., 0);
+    }
+
+    #[test]
+    fn test_bootstrap() {
+        let context = FheContext::new();
+        let client_key = context.client_key();
+        let (integer_key, ..) = context.server_key().clone().into_raw_parts();
+        for value in [3.15, -0.75, 0.] {
+            let x = TfheFixed32::from(client_key, value, 16);
+            test_assert_encrypted_eq(
+                client_key,
+                &x.bootstrap(&integer_key),
+                value,
+                0,
+            );
+        }
+    }
+
+    #[test]
+    fn test_apply_blockwise_via_pbs() {
+        let context = FheContext::new();
+        let client_key = context.client_key();
+        let (integer_key, ..) = context.server_key().clone().into_raw_parts();
+        let modulus = integer_key.message_modulus().0 as u64;
+        for value in [2.5, -0.75, 0.] {
+            let x = TfheFixed32::from(client_key, value, 16);
+            let raw = Fixed32::from(value, 16).value();
+
+            // Complementing every digit is the bitwise not
+            let not = x.clone().apply_blockwise_via_pbs(
+                |digit| modulus - 1 - digit,
+                &integer_key,
+            );
+            test_assert_encrypted_raw_eq(client_key, &not, !raw, 0);
+
+            let same = x.apply_blockwise_via_pbs(|digit| digit, &integer_key);
+            test_assert_encrypted_raw_eq(client_key, &same, raw, 0);
+        }
+    }
+
+    #[test]
+    fn test_abs_sign_relu() {
+        let context = FheContext::new();
+        let client_key = context.client_key();
+        let (integer_key, ..) = context.server_key().clone().into_raw_parts();
+        for value in [2.5, -0.75, 0., -30000.] {
+            let x = TfheFixed32::from(client_key, value, 16);
+            let expected = Fixed32::from(value, 16);
+            test_assert_encrypted_eq(
+                client_key,
+                &x.clone().abs(&integer_key),
+                value.abs(),
+                0,
+            );
+            assert_eq!(
+                x.clone().sign(&integer_key).to_fixed32(client_key),
+                expected.signum_fixed()
+            );
+            test_assert_encrypted_eq(
+                client_key,
+                &x.relu(&integer_key),
+                value.max(0.),
+                0,
+            );
+        }
+    }
+
+    #[test]
+    fn test_shift_encrypted() {
+        let context = FheContext::new();
+        let client_key = context.client_key();
+        for (value, n) in [(3.25, 2), (-3.25, 2), (0.75, 0), (-1.5, 40)] {
+            let x = Fixed32::from(value, 16);
+            let encrypted = TfheFixed32::from(client_key, value, 16);
+            assert_eq!(
+                encrypted
+                    .clone()
+                    .shift_right_encrypted(n)
+                    .to_fixed32(client_key),
+                x.shift_right(n)
+            );
+            assert_eq!(
+                encrypted.shift_left_encrypted(n).to_fixed32(client_key),
+                x.shift_left(n).unwrap_or(Fixed32::new(0, 16))
+            );
+        }
+
+        // Shifting right by one halves the value
+        let x = TfheFixed32::from(client_key, 5., 16);
+        test_assert_encrypted_eq(
+            client_key,
+            &x.shift_right_encrypted(1),
+            2.5,
+            0,
+        );
+    }
+
+    #[test]
+    fn test_rotate_encrypted() {
+        let context = FheContext::new();
+        let client_key = context.client_key();
+        for value in [1.5, -0.25, 100.125] {
+            let raw = Fixed32::from(value, 16).value();
+            let x = TfheFixed32::from(client_key, value, 16);
+            for n in [0, 1, 7, 31, 33] {
+                let left = x.clone().rotate_left_encrypted(n);
+                assert_eq!(
+                    left.to_fixed32(client_key),
+                    Fixed32::new(raw.rotate_left(n), 16)
+                );
+                let round_trip = left.rotate_right_encrypted(n);
+                assert_eq!(round_trip.to_fixed32(client_key).value(), raw);
+            }
+        }
+    }
+
+    #[test]
+    fn test_homomorphic_if_then_else() {
+        // 2x if x >= 0 else x^2. The then branch runs even for negative x
+        let context = FheContext::new();
+        let client_key = context.client_key();
+        for (value, expected) in [(1.5, 3.), (-1.5, 2.25)] {
+            let x = TfheFixed32::from(client_key, value, 24);
+            let cond = x.ge_plaintext(Fixed32::new(0, 24));
+            let mut evaluated = 0;
+            let result = TfheFixed32::homomorphic_if_then_else(
+                cond,
+                || {
+                    evaluated += 1;
+                    x.clone().mul_plaintext(Fixed32::from(2., 24))
+                },
+                || x.clone() * x.clone(),
+            );
+            assert_eq!(evaluated, 1);
+            test_assert_encrypted_eq(client_key, &result, expected, 0);
+        }
+    }
+
+    #[test]
+    fn test_bit_vector_round_trip() {
+        let context = FheContext::new();
+        let client_key = context.client_key();
+        for value in [0., 1.5, -3.15, 100.25] {
+            let bits = Fixed32::from(value, 16).to_bit_vector();
+            let x = TfheFixed32::encrypt_bit_vector(client_key, bits, 16);
+            test_assert_encrypted_eq(
+                client_key,
+                &x,
+                Fixed32::from(value, 16).to_f32(),
+                0,
+            );
+            assert_eq!(x.decrypt_bit_vector(client_key), bits);
+        }
+    }
+
+    #[test]
+    fn test_add_many() {
+        let config = ConfigBuilder::default().build();
+        let (client_key, server_key) = generate_keys(config);
+
+        set_server_key(server_key.clone());
+        let power_of_two = [1.5, -0.25, 3., 0.75];
+        let values = TfheFixed32::encrypt_slice(&client_key, &power_of_two, 16);
+        let sum = TfheFixed32::add_many(values, &server_key);
+        test_assert_encrypted_eq(&client_key, &sum, 5., 0);
+
+        let odd = [1., 2., 3., 4., -0.5];
+        let values = TfheFixed32::encrypt_slice(&client_key, &odd, 16);
+        let sum = TfheFixed32::add_many(values, &server_key);
+        test_assert_encrypted_eq(&client_key, &sum, 9.5, 0);
+        assert_eq!(sum.exp(), 16);
+
+        let single = TfheFixed32::encrypt_slice(&client_key, &[2.5], 16);
+        test_assert_encrypted_eq(
+            &client_key,
+            &TfheFixed32::add_many(single, &server_key),
+            2.5,
+            0,
+        );
     }
 }
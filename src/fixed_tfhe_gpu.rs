@@ -0,0 +1,153 @@
+use crate::fixed_tfhe::TfheFixed32;
+use std::ops::{
+    Add,
+    Mul,
+    Sub,
+};
+use tfhe::{
+    set_server_key,
+    ClientKey,
+    CompressedServerKey,
+};
+
+// CUDA-accelerated `TfheFixed32`. Build with `--features gpu`, which enables
+// the `gpu` feature of TFHE-rs; that needs the CUDA toolkit (nvcc) and a
+// supported NVIDIA GPU on the build machine. The high-level TFHE-rs API has no
+// separate GPU integer type: `FheInt32` runs on whichever device the thread's
+// server key lives on. Call `set_gpu_server_key` once per thread before
+// computing; without it the operators below run on the CPU
+
+pub fn set_gpu_server_key(compressed_server_key: &CompressedServerKey) {
+    // Uploads the key to the GPU and makes it the current thread's key
+    set_server_key(compressed_server_key.decompress_to_gpu());
+}
+
+#[derive(Clone)]
+pub struct TfheFixed32Gpu {
+    // The encrypted number; its operations are dispatched to the GPU
+    inner: TfheFixed32,
+}
+
+impl TfheFixed32Gpu {
+    pub fn new(inner: TfheFixed32) -> Self {
+        Self { inner }
+    }
+
+    pub fn from<T: Into<f32>>(
+        client_key: &ClientKey,
+        value: T,
+        exp: u32,
+    ) -> TfheFixed32Gpu {
+        Self::new(TfheFixed32::from(client_key, value, exp))
+    }
+
+    pub fn exp(&self) -> u32 {
+        self.inner.exp()
+    }
+
+    pub fn to_f32(&self, client_key: &ClientKey) -> f32 {
+        self.inner.to_f32(client_key)
+    }
+
+    pub fn inner(&self) -> &TfheFixed32 {
+        &self.inner
+    }
+
+    pub fn into_inner(self) -> TfheFixed32 {
+        self.inner
+    }
+}
+
+impl Add for TfheFixed32Gpu {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self::Output {
+        Self::new(self.inner + other.inner)
+    }
+}
+
+impl Sub for TfheFixed32Gpu {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self::Output {
+        Self::new(self.inner - other.inner)
+    }
+}
+
+impl Mul for TfheFixed32Gpu {
+    type Output = Self;
+
+    fn mul(self, other: Self) -> Self::Output {
+        Self::new(self.inner * other.inner)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        measure::measure_duration,
+        test_utils::test_assert_encrypted_eq,
+    };
+    use tfhe::ConfigBuilder;
+
+    #[test]
+    fn test_gpu_arithmetic() {
+        let config = ConfigBuilder::default().build();
+        let client_key = ClientKey::generate(config);
+
+        set_gpu_server_key(&CompressedServerKey::new(&client_key));
+        let a = TfheFixed32Gpu::from(&client_key, 1.5, 16);
+        let b = TfheFixed32Gpu::from(&client_key, -2.25, 16);
+        let check = |x: TfheFixed32Gpu, expected| {
+            test_assert_encrypted_eq(&client_key, x.inner(), expected, 0)
+        };
+        check(a.clone() + b.clone(), -0.75);
+        check(a.clone() - b.clone(), 3.75);
+        check(a * b, -3.375);
+    }
+
+    #[test]
+    #[ignore]
+    fn bench_gpu_vs_cpu_mul() {
+        // Run with `cargo test --release --features gpu -- --ignored`
+        let config = ConfigBuilder::default().build();
+        let client_key = ClientKey::generate(config);
+        let compressed_server_key = CompressedServerKey::new(&client_key);
+        let xs: Vec<f32> = (0..1000).map(|i| 0.01 * i as f32 - 5.).collect();
+        let a = TfheFixed32::encrypt_slice(&client_key, &xs, 16);
+        let b = TfheFixed32::encrypt_slice(&client_key, &xs, 16);
+
+        set_server_key(compressed_server_key.decompress());
+        let (cpu, cpu_time) = measure_duration(|| {
+            a.iter()
+                .zip(&b)
+                .map(|(x, y)| x.clone() * y.clone())
+                .collect::<Vec<_>>()
+        });
+
+        set_gpu_server_key(&compressed_server_key);
+        let (gpu, gpu_time) = measure_duration(|| {
+            a.iter()
+                .zip(&b)
+                .map(|(x, y)| {
+                    TfheFixed32Gpu::new(x.clone())
+                        * TfheFixed32Gpu::new(y.clone())
+                })
+                .collect::<Vec<_>>()
+        });
+
+        assert_eq!(
+            TfheFixed32::decrypt_slice(&client_key, &cpu),
+            gpu.iter()
+                .map(|x| x.to_f32(&client_key))
+                .collect::<Vec<_>>()
+        );
+        println!(
+            "1000 multiplications; cpu: {:.3?}, gpu: {:.3?}, speedup: {:.2}x",
+            cpu_time,
+            gpu_time,
+            cpu_time.as_secs_f64() / gpu_time.as_secs_f64()
+        );
+    }
+}
@@ -0,0 +1,131 @@
+use crate::fixed::Fixed32;
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+
+#[cfg(feature = "rayon")]
+pub fn par_map(
+    values: &[Fixed32],
+    f: impl Fn(Fixed32) -> Fixed32 + Sync,
+) -> Vec<Fixed32> {
+    values.par_iter().map(|&x| f(x)).collect()
+}
+
+#[cfg(feature = "rayon")]
+pub fn par_sum(values: &[Fixed32]) -> Fixed32 {
+    // Zero with `exp = 0` is the identity because `Add` aligns exponents
+    values
+        .par_iter()
+        .cloned()
+        .reduce(|| Fixed32::new(0, 0), |a, b| a + b)
+}
+
+#[cfg(feature = "rayon")]
+pub fn par_dot_product(a: &[Fixed32], b: &[Fixed32]) -> Fixed32 {
+    if a.len() != b.len() {
+        panic!("Only support dot product between slices of the same length!")
+    }
+
+    a.par_iter()
+        .zip(b)
+        .map(|(&x, &y)| x * y)
+        .reduce(|| Fixed32::new(0, 0), |a, b| a + b)
+}
+
+pub fn partial_products(values: &[Fixed32]) -> Vec<Fixed32> {
+    // Running products [v0, v0 * v1, v0 * v1 * v2, ...]. Each step
+    // multiplies in 64 bits and truncates back like `Mul`
+    let Some(first) = values.first() else {
+        return Vec::new();
+    };
+    let exp = first.exp();
+    if values.iter().any(|x| x.exp() != exp) {
+        panic!("Only support prefix products between fixed-point numbers with the same exponential!")
+    }
+
+    let mut product: i64 = 1 << exp;
+    let mut products = Vec::with_capacity(values.len());
+    for x in values {
+        product = ((product * x.value() as i64) >> exp) as i32 as i64;
+        products.push(Fixed32::new(product as i32, exp));
+    }
+
+    products
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_partial_products() {
+        let values = [2., 3., 4.].map(|x| Fixed32::from(x, 16));
+        let expected = [2., 6., 24.].map(|x| Fixed32::from(x, 16));
+        assert_eq!(partial_products(&values), expected);
+
+        let values = [0.5, -0.25, 8.].map(|x| Fixed32::from(x, 24));
+        let expected = [0.5, -0.125, -1.].map(|x| Fixed32::from(x, 24));
+        assert_eq!(partial_products(&values), expected);
+        assert_eq!(partial_products(&[]), []);
+    }
+
+    #[cfg(feature = "rayon")]
+    fn sample_values(len: usize) -> Vec<Fixed32> {
+        (0..len)
+            .map(|i| Fixed32::from(0.5 + (i % 100) as f32 / 10., 16))
+            .collect()
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn test_par_map_matches_sequential() {
+        let values = sample_values(1000);
+        let sequential: Vec<Fixed32> =
+            values.iter().map(|x| x.reciprocal()).collect();
+        assert_eq!(par_map(&values, |x| x.reciprocal()), sequential);
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn test_par_sum_matches_sequential() {
+        let values = sample_values(1000);
+        let sequential =
+            values.iter().fold(Fixed32::new(0, 16), |acc, &x| acc + x);
+        assert_eq!(par_sum(&values), sequential);
+        assert_eq!(par_sum(&[]).to_f32(), 0.);
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn test_par_dot_product_matches_sequential() {
+        let a = sample_values(1000);
+        let b: Vec<Fixed32> = a.iter().rev().cloned().collect();
+        let sequential = a
+            .iter()
+            .zip(&b)
+            .fold(Fixed32::new(0, 16), |acc, (&x, &y)| acc + x * y);
+        assert_eq!(par_dot_product(&a, &b), sequential);
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    #[ignore]
+    fn bench_par_map_speedup() {
+        // Run with `cargo test --release --features rayon -- --ignored`
+        use crate::measure::measure_duration;
+
+        let values = sample_values(10000);
+        let (sequential, sequential_time) = measure_duration(|| {
+            values.iter().map(|x| x.reciprocal()).collect::<Vec<_>>()
+        });
+        let (parallel, parallel_time) =
+            measure_duration(|| par_map(&values, |x| x.reciprocal()));
+
+        assert_eq!(sequential, parallel);
+        println!(
+            "sequential: {:.3?}, parallel: {:.3?}, speedup: {:.2}x",
+            sequential_time,
+            parallel_time,
+            sequential_time.as_secs_f64() / parallel_time.as_secs_f64()
+        );
+    }
+}
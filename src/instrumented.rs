@@ -0,0 +1,194 @@
+use crate::{
+    fixed::Fixed32,
+    fixed_tfhe::TfheFixed32,
+};
+use std::{
+    ops::{
+        Add,
+        Div,
+        Mul,
+        Sub,
+    },
+    sync::{
+        Arc,
+        Mutex,
+    },
+};
+use tfhe::FheBool;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct OpCounts {
+    pub add_count: u64,
+    pub sub_count: u64,
+    pub mul_count: u64,
+    pub comparison_count: u64,
+}
+
+#[derive(Clone)]
+pub struct TfheFixed32Instrumented {
+    // The wrapped encrypted value
+    inner: TfheFixed32,
+
+    // Counters shared by every value derived from the same inputs. Binary
+    // operations record into the left operand's counters
+    stats: Arc<Mutex<OpCounts>>,
+}
+
+impl TfheFixed32 {
+    pub fn count_operations(self) -> TfheFixed32Instrumented {
+        TfheFixed32Instrumented::new(self)
+    }
+}
+
+impl TfheFixed32Instrumented {
+    pub fn new(inner: TfheFixed32) -> Self {
+        // Starts a fresh set of counters
+        Self {
+            inner,
+            stats: Arc::new(Mutex::new(OpCounts::default())),
+        }
+    }
+
+    pub fn wrap(&self, inner: TfheFixed32) -> Self {
+        // Wraps another input of the same circuit, sharing the counters
+        Self {
+            inner,
+            stats: Arc::clone(&self.stats),
+        }
+    }
+
+    pub fn inner(&self) -> &TfheFixed32 {
+        &self.inner
+    }
+
+    pub fn into_inner(self) -> TfheFixed32 {
+        self.inner
+    }
+
+    pub fn drain_stats(&self) -> OpCounts {
+        // Returns the counts so far and resets them to zero
+        std::mem::take(&mut *self.stats.lock().unwrap())
+    }
+
+    pub fn ge_plaintext(&self, scalar: Fixed32) -> FheBool {
+        self.record(|counts| &mut counts.comparison_count);
+        self.inner.ge_plaintext(scalar)
+    }
+
+    pub fn reciprocal(self) -> Self {
+        // The same iteration as `TfheFixed32::reciprocal`, built from the
+        // counted operations
+        let exp = self.inner.exp() as i32;
+        let two = self
+            .wrap(TfheFixed32::trivial_from_fixed32(Fixed32::from(2., exp)));
+        let mut result = self.wrap(self.inner.reciprocal_guess());
+        for _ in 0..5 {
+            let t1 = result.clone() * self.clone();
+            result = result * (two.clone() - t1);
+        }
+
+        result
+    }
+
+    fn record(&self, counter: impl FnOnce(&mut OpCounts) -> &mut u64) {
+        *counter(&mut self.stats.lock().unwrap()) += 1;
+    }
+
+    fn with_inner(self, inner: TfheFixed32) -> Self {
+        Self {
+            inner,
+            stats: self.stats,
+        }
+    }
+}
+
+impl Add for TfheFixed32Instrumented {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self::Output {
+        self.record(|counts| &mut counts.add_count);
+        let inner = self.inner.clone() + other.inner;
+        self.with_inner(inner)
+    }
+}
+
+impl Sub for TfheFixed32Instrumented {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self::Output {
+        self.record(|counts| &mut counts.sub_count);
+        let inner = self.inner.clone() - other.inner;
+        self.with_inner(inner)
+    }
+}
+
+impl Mul for TfheFixed32Instrumented {
+    type Output = Self;
+
+    fn mul(self, other: Self) -> Self::Output {
+        self.record(|counts| &mut counts.mul_count);
+        let inner = self.inner.clone() * other.inner;
+        self.with_inner(inner)
+    }
+}
+
+impl Div for TfheFixed32Instrumented {
+    type Output = Self;
+
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn div(self, other: Self) -> Self::Output {
+        // Counted as the reciprocal's operations plus one multiplication
+        self * other.reciprocal()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        fixed_tfhe::key_management::FheContext,
+        test_utils::test_assert_encrypted_eq,
+    };
+    use tfhe::prelude::FheDecrypt;
+
+    #[test]
+    fn test_count_arithmetic() {
+        let context = FheContext::new();
+        let client_key = context.client_key();
+        let x = TfheFixed32::from(client_key, 1.5, 16).count_operations();
+        let y = x.wrap(TfheFixed32::from(client_key, 2., 16));
+
+        let result = (x.clone() + y.clone()) * y.clone() - x.clone();
+        assert!(x.ge_plaintext(Fixed32::from(1., 16)).decrypt(client_key));
+        test_assert_encrypted_eq(client_key, result.inner(), 5.5, 0);
+
+        let expected = OpCounts {
+            add_count: 1,
+            sub_count: 1,
+            mul_count: 1,
+            comparison_count: 1,
+        };
+        assert_eq!(result.drain_stats(), expected);
+        assert_eq!(x.drain_stats(), OpCounts::default());
+    }
+
+    #[test]
+    fn test_count_reciprocal() {
+        let context = FheContext::new();
+        let client_key = context.client_key();
+        let x = TfheFixed32::from(client_key, 3.15, 24).count_operations();
+        let result = x.reciprocal();
+        assert_eq!(
+            result.inner().to_fixed32(client_key),
+            TfheFixed32::from(client_key, 3.15, 24)
+                .reciprocal()
+                .to_fixed32(client_key)
+        );
+
+        // Two multiplications and a subtraction per Newton-Raphson iteration
+        let counts = result.drain_stats();
+        assert_eq!(counts.mul_count, 10);
+        assert_eq!(counts.sub_count, 5);
+        assert_eq!(counts.add_count, 0);
+    }
+}
@@ -0,0 +1,26 @@
+pub mod approx;
+pub mod calculus;
+pub mod checked;
+pub mod dsp;
+pub mod error_analysis;
+pub mod ffi;
+pub mod fixed;
+pub mod fixed16;
+pub mod fixed_tfhe;
+pub mod fixed_vec;
+pub mod float;
+pub mod instrumented;
+pub mod measure;
+pub mod ml;
+pub mod nn;
+pub mod stats;
+pub mod traced;
+pub mod trig;
+#[cfg(feature = "gpu")]
+pub mod fixed_tfhe_gpu;
+#[cfg(feature = "pyo3")]
+pub mod python;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+#[cfg(test)]
+mod test_utils;
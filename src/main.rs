@@ -1,9 +1,10 @@
-use crate::fixed::Fixed32;
-
-mod fixed;
-mod fixed_tfhe;
-mod measure;
-mod float;
+use fixed_point_arithmetic::{
+    fixed::Fixed32,
+    measure::{
+        compare_approximations,
+        generate_accuracy_report,
+    },
+};
 
 fn main() {
     // let x = 1160f32;
@@ -20,4 +21,30 @@ fn main() {
     let x = Fixed32::from(5., 24);
     println!("real reciprocal: {}", 1. / 5.);
     println!("approx reciprocal: {}", x.reciprocal().to_f32());
+
+    // Newton-Raphson only needs multiplications and additions, which are
+    // cheap in FHE, while long division is not available on ciphertexts
+    let approx_fns: [(&str, &dyn Fn(f32) -> Fixed32); 2] = [
+        ("newton-raphson", &|x| Fixed32::from(x, 24).reciprocal()),
+        ("long division", &|x| {
+            let divisor = Fixed32::from(x, 24);
+            let quotient = (1i64 << 48) / divisor.value() as i64;
+            Fixed32::new(quotient as i32, 24)
+        }),
+    ];
+    let test_points = [0.008375, 0.22, 0.5, 1., 3.15, 5., 13., 107.4];
+    println!(
+        "{}",
+        compare_approximations(|x| 1. / x, &approx_fns, &test_points)
+    );
+
+    let samples: Vec<f32> = (1..=1000).map(|i| i as f32 / 100.).collect();
+    println!(
+        "{}",
+        generate_accuracy_report(
+            |x| Fixed32::from(x, 24).reciprocal(),
+            |x| 1. / x,
+            &samples,
+        )
+    );
 }
@@ -1,4 +1,11 @@
-use std::time::Instant;
+use crate::fixed::Fixed32;
+use std::fmt;
+// `Instant` is not available on wasm32 targets
+#[cfg(not(target_arch = "wasm32"))]
+use std::time::{
+    Duration,
+    Instant,
+};
 
 pub fn diff<T>(exact: T, approximation: T) -> f32
 where
@@ -9,9 +16,585 @@ where
     (exact_f32 - approximation_f32).abs() / exact_f32
 }
 
+pub fn relative_error_percent<T: Into<f32> + Copy>(exact: T, approx: T) -> f32 {
+    diff(exact, approx) * 100.0
+}
+
+pub fn absolute_error<T>(exact: T, approx: T) -> f32
+where
+    T: Into<f32> + Copy,
+{
+    // Unlike `diff`, stays meaningful when `exact` is zero or close to it
+    (exact.into() - approx.into()).abs()
+}
+
+pub fn max_error_in_slice(exact: &[f32], approx: &[f32]) -> (f32, f32) {
+    // Returns `(max_absolute, max_relative)`. Points where the exact value
+    // is zero only count toward the absolute error
+    if exact.len() != approx.len() {
+        panic!("The exact and approximate slices must have the same length!")
+    }
+
+    exact
+        .iter()
+        .zip(approx)
+        .fold((0., 0.), |(abs, rel), (&e, &a)| {
+            let rel = if e == 0. {
+                rel
+            } else {
+                f32::max(rel, diff(e, a).abs())
+            };
+            (f32::max(abs, absolute_error(e, a)), rel)
+        })
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[deprecated(note = "returns milliseconds as `f64`; use `measure_duration`")]
 pub fn measure_time<F: FnOnce() -> T, T>(closure: F) -> (T, f64) {
+    let (result, elapsed_time) = measure_duration(closure);
+    (result, elapsed_time.as_secs_f64() * 1000.)
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub fn measure_duration<F: FnOnce() -> T, T>(closure: F) -> (T, Duration) {
     let start_time = Instant::now();
     let result = closure();
-    let elapsed_time = start_time.elapsed().as_secs_f64();
-    (result, elapsed_time * 1000.)
+    (result, start_time.elapsed())
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub fn measure_time_nanos<F: FnOnce() -> T, T>(closure: F) -> (T, u128) {
+    // Milliseconds round the fastest operations (e.g. additions) to zero
+    let (result, elapsed_time) = measure_duration(closure);
+    (result, elapsed_time.as_nanos())
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub fn compare_plaintext_fhe<F1, F2, T1, T2>(
+    plain_op: F1,
+    fhe_op: F2,
+    label: &str,
+) -> f64
+where
+    F1: FnOnce() -> T1,
+    F2: FnOnce() -> T2,
+{
+    // Times one run of each version and prints how many times slower the
+    // encrypted version is; the overhead factor is also returned
+    let (_, plain_time) = measure_duration(plain_op);
+    let (_, fhe_time) = measure_duration(fhe_op);
+    let plain_ms = plain_time.as_secs_f64() * 1000.;
+    let fhe_ms = fhe_time.as_secs_f64() * 1000.;
+    let overhead = fhe_ms / plain_ms;
+
+    println!("{}", label);
+    println!("  plaintext: {:>12.6} ms", plain_ms);
+    println!("  fhe:       {:>12.3} ms", fhe_ms);
+    println!("  overhead:  {:>12.1}x", overhead);
+
+    overhead
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WorstCaseReport {
+    // The grid point with the largest absolute error
+    pub input: Fixed32,
+
+    // What the approximation returned at `input`
+    pub output: Fixed32,
+
+    // The absolute error at `input`
+    pub absolute_error: f64,
+
+    // The absolute error divided by the exact magnitude; infinite if the
+    // exact value is zero but the output is not
+    pub relative_error: f64,
+}
+
+pub fn worst_case_error(
+    f_approx: impl Fn(Fixed32) -> Fixed32,
+    f_exact: impl Fn(f64) -> f64,
+    start: Fixed32,
+    end: Fixed32,
+    steps: u32,
+) -> WorstCaseReport {
+    // Evaluates `steps + 1` evenly spaced points from `start` to `end`
+    // inclusive, rounded to the grid of representable values, and reports
+    // the one with the largest absolute error
+    if start.exp() != end.exp() {
+        panic!("Only support grids between two fixed-point numbers with the same exponential!")
+    }
+    if steps == 0 {
+        panic!("The grid must have at least one step!")
+    }
+
+    let exp = start.exp();
+    let span = end.value() as i64 - start.value() as i64;
+    let mut worst: Option<WorstCaseReport> = None;
+    for i in 0..=steps as i64 {
+        let input = Fixed32::new(
+            (start.value() as i64 + span * i / steps as i64) as i32,
+            exp,
+        );
+        let output = f_approx(input);
+        let exact = f_exact(fixed_to_f64(input));
+        let absolute_error = (fixed_to_f64(output) - exact).abs();
+        if worst.is_none_or(|w| absolute_error > w.absolute_error) {
+            let relative_error = match absolute_error {
+                0. => 0.,
+                _ => absolute_error / exact.abs(),
+            };
+            worst = Some(WorstCaseReport {
+                input,
+                output,
+                absolute_error,
+                relative_error,
+            });
+        }
+    }
+
+    worst.unwrap()
+}
+
+pub struct ErrorHistogram {
+    // `(bin_center, count)` pairs of relative errors, from the smallest
+    // error up
+    pub bins: Vec<(f32, usize)>,
+}
+
+pub fn error_histogram(
+    f_approx: impl Fn(Fixed32) -> Fixed32,
+    f_exact: impl Fn(f64) -> f64,
+    range: (f32, f32),
+    bins: usize,
+    n_samples: usize,
+) -> ErrorHistogram {
+    // Evaluates `n_samples` evenly spaced points over the inclusive range and
+    // buckets their relative errors into `bins` equal bins from zero to the
+    // largest error. Inputs use the largest exponential that fits the range;
+    // points where the exact value is zero are skipped
+    if bins == 0 || n_samples == 0 {
+        panic!("The error histogram needs at least one bin and one sample!")
+    }
+
+    let (start, end) = range;
+    let magnitude = f32::max(start.abs(), end.abs()) as f64;
+    let exp = (0..=30)
+        .rev()
+        .find(|&exp| magnitude * 2f64.powi(exp) < i32::MAX as f64)
+        .unwrap_or(0);
+
+    let errors: Vec<f64> = (0..n_samples)
+        .filter_map(|i| {
+            let t = match n_samples {
+                1 => 0.,
+                _ => i as f64 / (n_samples - 1) as f64,
+            };
+            let input = Fixed32::from_f64_clamped(
+                start as f64 + (end - start) as f64 * t,
+                exp,
+            );
+            let exact = f_exact(fixed_to_f64(input));
+            let output = fixed_to_f64(f_approx(input));
+            (exact != 0.).then(|| ((output - exact) / exact).abs())
+        })
+        .collect();
+
+    let max_error = errors.iter().cloned().fold(0., f64::max);
+    let width = max_error / bins as f64;
+    let mut counts = vec![0; bins];
+    for error in errors {
+        let bin = match width {
+            0. => 0,
+            _ => ((error / width) as usize).min(bins - 1),
+        };
+        counts[bin] += 1;
+    }
+
+    ErrorHistogram {
+        bins: counts
+            .into_iter()
+            .enumerate()
+            .map(|(i, count)| (((i as f64 + 0.5) * width) as f32, count))
+            .collect(),
+    }
+}
+
+impl fmt::Display for ErrorHistogram {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // One row per bin, with bars scaled so the fullest bin is 50 wide
+        let max_count = self.bins.iter().map(|&(_, c)| c).max().unwrap_or(0);
+        for &(center, count) in &self.bins {
+            let width = match max_count {
+                0 => 0,
+                _ => (count * 50).div_ceil(max_count),
+            };
+            writeln!(
+                f,
+                "{:>12.3e} | {:<50} {}",
+                center,
+                "#".repeat(width),
+                count
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+fn fixed_to_f64(x: Fixed32) -> f64 {
+    // Exact, unlike `to_f32`, which rounds to 24 significant bits
+    x.value() as f64 / 2f64.powi(x.exp())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AccuracyReport {
+    pub max_relative_error: f32,
+    pub max_absolute_error: f32,
+    pub mean_relative_error: f32,
+    pub rmse: f32,
+    pub num_samples: usize,
+
+    // The sample with the largest relative error
+    pub worst_input: f32,
+    pub worst_output: f32,
+    pub worst_expected: f32,
+}
+
+pub fn generate_accuracy_report(
+    approx: impl Fn(f32) -> Fixed32,
+    exact: impl Fn(f32) -> f32,
+    samples: &[f32],
+) -> AccuracyReport {
+    // As in `max_error_in_slice`, samples where the exact value is zero only
+    // count toward the absolute errors
+    if samples.is_empty() {
+        panic!("The accuracy report needs at least one sample!")
+    }
+
+    let mut report = AccuracyReport {
+        max_relative_error: 0.,
+        max_absolute_error: 0.,
+        mean_relative_error: 0.,
+        rmse: 0.,
+        num_samples: samples.len(),
+        worst_input: samples[0],
+        worst_output: approx(samples[0]).to_f32(),
+        worst_expected: exact(samples[0]),
+    };
+    let mut relative_sum = 0.;
+    let mut relative_count = 0;
+    let mut squared_sum = 0.;
+    for &x in samples {
+        let output = approx(x).to_f32();
+        let expected = exact(x);
+        let absolute = absolute_error(expected, output);
+        report.max_absolute_error =
+            f32::max(report.max_absolute_error, absolute);
+        squared_sum += absolute * absolute;
+
+        if expected != 0. {
+            let relative = diff(expected, output).abs();
+            relative_sum += relative;
+            relative_count += 1;
+            if relative > report.max_relative_error {
+                report.max_relative_error = relative;
+                report.worst_input = x;
+                report.worst_output = output;
+                report.worst_expected = expected;
+            }
+        }
+    }
+
+    if relative_count > 0 {
+        report.mean_relative_error = relative_sum / relative_count as f32;
+    }
+    report.rmse = (squared_sum / samples.len() as f32).sqrt();
+
+    report
+}
+
+impl fmt::Display for AccuracyReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{:<22} {:>12}", "samples", self.num_samples)?;
+        writeln!(
+            f,
+            "{:<22} {:>12.3e}",
+            "max relative error", self.max_relative_error
+        )?;
+        writeln!(
+            f,
+            "{:<22} {:>12.3e}",
+            "mean relative error", self.mean_relative_error
+        )?;
+        writeln!(
+            f,
+            "{:<22} {:>12.3e}",
+            "max absolute error", self.max_absolute_error
+        )?;
+        writeln!(f, "{:<22} {:>12.3e}", "rmse", self.rmse)?;
+        writeln!(
+            f,
+            "{:<22} {:>12} -> {} (expected {})",
+            "worst input",
+            self.worst_input,
+            self.worst_output,
+            self.worst_expected
+        )
+    }
+}
+
+pub struct AlgorithmErrors {
+    pub name: String,
+    pub max_error: f32,
+    pub mean_error: f32,
+}
+
+pub struct ComparisonReport {
+    pub algorithms: Vec<AlgorithmErrors>,
+}
+
+pub fn compare_approximations(
+    exact_fn: impl Fn(f32) -> f32,
+    approx_fns: &[(&str, impl Fn(f32) -> Fixed32)],
+    test_points: &[f32],
+) -> ComparisonReport {
+    // Measures the relative error (see `diff`) of every approximation at every
+    // test point
+    let algorithms = approx_fns
+        .iter()
+        .map(|(name, approx_fn)| {
+            let errors: Vec<f32> = test_points
+                .iter()
+                .map(|&x| diff(exact_fn(x), approx_fn(x).to_f32()).abs())
+                .collect();
+            let max_error = errors.iter().cloned().fold(0., f32::max);
+            let mean_error = errors.iter().sum::<f32>() / errors.len() as f32;
+
+            AlgorithmErrors {
+                name: name.to_string(),
+                max_error,
+                mean_error,
+            }
+        })
+        .collect();
+
+    ComparisonReport { algorithms }
+}
+
+impl fmt::Display for ComparisonReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{:<24} {:>12} {:>12}", "algorithm", "max", "mean")?;
+        for algorithm in &self.algorithms {
+            writeln!(
+                f,
+                "{:<24} {:>12.3e} {:>12.3e}",
+                algorithm.name, algorithm.max_error, algorithm.mean_error
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compare_approximations() {
+        let approx_fns: [(&str, &dyn Fn(f32) -> Fixed32); 2] = [
+            ("exact", &|x| Fixed32::from(x * 2., 24)),
+            ("off by one", &|x| Fixed32::from(x * 2. + 1., 24)),
+        ];
+        let report =
+            compare_approximations(|x| x * 2., &approx_fns, &[0.5, 1., 2.]);
+
+        assert_eq!(report.algorithms.len(), 2);
+        assert_eq!(report.algorithms[0].name, "exact");
+        assert_eq!(report.algorithms[0].max_error, 0.);
+        assert_eq!(report.algorithms[1].max_error, 1.);
+        assert_eq!(report.algorithms[1].mean_error, 1.75 / 3.);
+
+        let table = report.to_string();
+        assert!(table.starts_with("algorithm"));
+        assert_eq!(table.lines().count(), 3);
+    }
+
+    #[test]
+    fn test_compare_plaintext_fhe() {
+        let overhead = compare_plaintext_fhe(
+            || Fixed32::from(3.15, 24).reciprocal(),
+            || std::thread::sleep(std::time::Duration::from_millis(5)),
+            "reciprocal",
+        );
+        assert!(overhead > 1.);
+    }
+
+    #[test]
+    fn test_relative_error_percent() {
+        assert_eq!(relative_error_percent(2., 2.5), 25.);
+        assert_eq!(relative_error_percent(4., 4.), 0.);
+    }
+
+    #[test]
+    fn test_measure_time_nanos() {
+        let a = Fixed32::from(1.5, 16);
+        let b = Fixed32::from(2.25, 16);
+        let (sum, _) = measure_time_nanos(|| a + b);
+        assert_eq!(sum, Fixed32::from(3.75, 16));
+    }
+
+    #[test]
+    #[ignore]
+    fn bench_measure_time_nanos() {
+        // Run with `cargo test --release -- --ignored`. Depends on the
+        // machine's clock, so it is not part of the regular tests. The first
+        // call pays for warming up the clock, so keep the fastest of several
+        // runs
+        let a = Fixed32::from(1.5, 16);
+        let b = Fixed32::from(2.25, 16);
+        let nanos = (0..100)
+            .map(|_| measure_time_nanos(|| a + b).1)
+            .min()
+            .unwrap();
+        assert!(nanos < 100, "took {} ns", nanos);
+    }
+
+    #[allow(deprecated)]
+    #[test]
+    fn test_measure_time_matches_duration() {
+        let (result, ms) = measure_time(|| {
+            std::thread::sleep(Duration::from_millis(2));
+            7
+        });
+        assert_eq!(result, 7);
+        assert!(ms >= 2.);
+    }
+
+    #[test]
+    fn test_worst_case_error() {
+        // An approximation of 2x that is one ULP high at every odd raw value
+        let report = worst_case_error(
+            |x| Fixed32::new(2 * x.value() + (x.value() & 1), x.exp()),
+            |x| 2. * x,
+            Fixed32::new(0, 4),
+            Fixed32::new(16, 4),
+            8,
+        );
+        assert_eq!(report.absolute_error, 0.);
+        assert_eq!(report.input, Fixed32::new(0, 4));
+
+        let report = worst_case_error(
+            |x| Fixed32::new(2 * x.value() + (x.value() & 1), x.exp()),
+            |x| 2. * x,
+            Fixed32::new(0, 4),
+            Fixed32::new(16, 4),
+            16,
+        );
+        assert_eq!(report.input, Fixed32::new(1, 4));
+        assert_eq!(report.output, Fixed32::new(3, 4));
+        assert_eq!(report.absolute_error, 1. / 16.);
+        assert_eq!(report.relative_error, 0.5);
+    }
+
+    #[test]
+    fn test_reciprocal_worst_case() {
+        // Documents the accuracy noted on `Fixed32::reciprocal`
+        let worst = |a: f32, b: f32| {
+            worst_case_error(
+                |x| x.reciprocal(),
+                |x| 1. / x,
+                Fixed32::from(a, 24),
+                Fixed32::from(b, 24),
+                10000,
+            )
+        };
+
+        assert!(worst(1., 1.5).relative_error < 1e-6);
+        assert!(worst(2., 3.).relative_error < 1e-6);
+        assert!(worst(1., 1.75).relative_error < 1e-3);
+        assert!(worst(1., 1.9).relative_error < 0.05);
+
+        // Just below the next power of two the initial guess is too far off
+        // for 5 iterations
+        let report = worst(0.5, 100.);
+        assert!(report.relative_error > 0.5);
+        assert!((0.99..1.).contains(&report.input.to_f32()));
+    }
+
+    #[test]
+    fn test_generate_accuracy_report() {
+        // One ULP high at 1, exact elsewhere
+        let report = generate_accuracy_report(
+            |x| {
+                let x = Fixed32::from(x, 4);
+                match x.value() {
+                    16 => Fixed32::new(17, 4),
+                    _ => x,
+                }
+            },
+            |x| x,
+            &[0., 1., 2., 3.],
+        );
+
+        assert_eq!(report.num_samples, 4);
+        assert_eq!(report.max_absolute_error, 1. / 16.);
+        assert_eq!(report.max_relative_error, 1. / 16.);
+        assert_eq!(report.mean_relative_error, 1. / 48.);
+        assert_eq!(report.rmse, 1. / 32.);
+        assert_eq!(report.worst_input, 1.);
+        assert_eq!(report.worst_output, 1.0625);
+        assert_eq!(report.worst_expected, 1.);
+
+        let table = report.to_string();
+        assert!(table.starts_with("samples"));
+        assert_eq!(table.lines().count(), 6);
+    }
+
+    #[test]
+    fn test_error_histogram() {
+        // The inputs get exp 29 for this range; truncating them to 9
+        // fractional bits gives relative errors below 2^-9
+        let histogram = error_histogram(
+            |x| Fixed32::new(x.value() >> 20 << 20, x.exp()),
+            |x| x,
+            (1., 2.),
+            4,
+            101,
+        );
+
+        assert_eq!(histogram.bins.len(), 4);
+        assert_eq!(histogram.bins.iter().map(|&(_, c)| c).sum::<usize>(), 101);
+        assert!(histogram.bins[3].0 < 1. / 512.);
+        assert!(histogram.bins.windows(2).all(|w| w[0].0 < w[1].0));
+
+        let chart = histogram.to_string();
+        assert_eq!(chart.lines().count(), 4);
+        assert!(chart.contains(&"#".repeat(50)));
+    }
+
+    #[test]
+    fn test_error_histogram_exact() {
+        let histogram = error_histogram(|x| x, |x| x, (-1., 1.), 3, 11);
+        // The single zero input is skipped
+        assert_eq!(histogram.bins, [(0., 10), (0., 0), (0., 0)]);
+    }
+
+    #[test]
+    fn test_absolute_error() {
+        assert_eq!(absolute_error(0., 0.25), 0.25);
+        assert_eq!(absolute_error(-1., 1.), 2.);
+        assert_eq!(absolute_error(3.5f32, 3.5), 0.);
+    }
+
+    #[test]
+    fn test_max_error_in_slice() {
+        let exact = [0., 1., -2., 4.];
+        let approx = [0.01, 1.1, -2.1, 4.];
+        let (max_absolute, max_relative) = max_error_in_slice(&exact, &approx);
+        assert!((max_absolute - 0.1).abs() < 1e-6);
+        assert!((max_relative - 0.1).abs() < 1e-6);
+        assert_eq!(max_error_in_slice(&[], &[]), (0., 0.));
+    }
 }
@@ -0,0 +1,265 @@
+use crate::{
+    fixed::Fixed32,
+    fixed_tfhe::TfheFixed32,
+};
+
+impl Fixed32 {
+    pub fn gradient_descent_step(
+        self,
+        gradient: Fixed32,
+        lr: Fixed32,
+    ) -> Fixed32 {
+        // self - lr * gradient
+        if self.exp() != gradient.exp() || self.exp() != lr.exp() {
+            panic!(
+                "Only support gradient descent between fixed-point numbers \
+            with the same exponential!"
+            )
+        }
+
+        self - lr * gradient
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn adam_step(
+        self,
+        gradient: Fixed32,
+        m1: &mut Fixed32,
+        m2: &mut Fixed32,
+        lr: Fixed32,
+        beta1: Fixed32,
+        beta2: Fixed32,
+        t: u32,
+    ) -> Fixed32 {
+        // One Adam update at step `t`, counting from 1. Updates the first and
+        // second moment estimates in place and returns the new parameter:
+        //
+        //   m1 <- beta1 * m1 + (1 - beta1) * g
+        //   m2 <- beta2 * m2 + (1 - beta2) * g^2
+        //   self - lr * m1' / (sqrt(m2') + eps)
+        //
+        // where m1' and m2' are the bias-corrected moments. The usual epsilon
+        // of 1e-8 is below the resolution of most exponentials, so one ULP is
+        // used instead
+        let exp = self.exp();
+        if [gradient, *m1, *m2, lr, beta1, beta2]
+            .iter()
+            .any(|x| x.exp() != exp)
+        {
+            panic!(
+                "Only support Adam between fixed-point numbers with the same \
+            exponential!"
+            )
+        }
+        if t == 0 {
+            panic!("The Adam step count starts at 1!")
+        }
+
+        let one = Fixed32::from(1., exp);
+        *m1 = beta1 * *m1 + (one - beta1) * gradient;
+        *m2 = beta2 * *m2 + (one - beta2) * gradient * gradient;
+
+        let m1_hat = div_i64(*m1, one - pow(beta1, t));
+        let m2_hat = div_i64(*m2, one - pow(beta2, t));
+        let denominator = sqrt(m2_hat) + Fixed32::new(1, exp);
+
+        self - lr * div_i64(m1_hat, denominator)
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn adam_step_encrypted(
+    param: TfheFixed32,
+    grad: TfheFixed32,
+    m1: &mut TfheFixed32,
+    m2: &mut TfheFixed32,
+    lr: Fixed32,
+    beta1: Fixed32,
+    beta2: Fixed32,
+    t: u32,
+) -> TfheFixed32 {
+    // `Fixed32::adam_step` on ciphertexts. The hyperparameters are public,
+    // so the bias corrections are folded into plaintext scalars and there is
+    // no encrypted division: lr / (1 - beta1^t) scales the first moment and
+    // the second goes through `rsqrt`, with one ULP added inside the square
+    // root as the epsilon. The exponential must be small enough for
+    // 1 / (1 - beta2) to fit, e.g. 16 for beta2 = 0.999
+    let exp = param.exp();
+    if [grad.exp(), m1.exp(), m2.exp()].iter().any(|&e| e != exp)
+        || [lr, beta1, beta2].iter().any(|x| x.exp() != exp as i32)
+    {
+        panic!(
+            "Only support Adam between fixed-point numbers with the same \
+        exponential!"
+        )
+    }
+    if t == 0 {
+        panic!("The Adam step count starts at 1!")
+    }
+
+    let one = Fixed32::from(1., exp as i32);
+    *m1 = m1.clone().mul_plaintext(beta1)
+        + grad.clone().mul_plaintext(one - beta1);
+    *m2 = m2.clone().mul_plaintext(beta2)
+        + (grad.clone() * grad).mul_plaintext(one - beta2);
+
+    let step_size = div_i64(lr, one - pow(beta1, t));
+    let m2_scale = div_i64(one, one - pow(beta2, t));
+    let inv_sqrt = m2
+        .clone()
+        .mul_plaintext(m2_scale)
+        .add_plaintext(Fixed32::new(1, exp as i32))
+        .rsqrt();
+
+    param - m1.clone().mul_plaintext(step_size) * inv_sqrt
+}
+
+fn pow(base: Fixed32, n: u32) -> Fixed32 {
+    // Repeated multiplication; stops early once the result underflows to zero
+    let mut result = Fixed32::from(1., base.exp());
+    for _ in 0..n {
+        result = result * base;
+        if result.value() == 0 {
+            break;
+        }
+    }
+
+    result
+}
+
+fn div_i64(a: Fixed32, b: Fixed32) -> Fixed32 {
+    // Long division in 64 bits, saturating to the 32-bit range. Unlike `Div`,
+    // which goes through `reciprocal`, this is exact to one ULP
+    let quotient = ((a.value() as i64) << a.exp()) / b.value() as i64;
+    Fixed32::new(
+        quotient.clamp(i32::MIN as i64, i32::MAX as i64) as i32,
+        a.exp(),
+    )
+}
+
+fn sqrt(x: Fixed32) -> Fixed32 {
+    // sqrt(v * 2^-e) * 2^e = isqrt(v * 2^e); negative inputs give zero
+    let scaled = (x.value().max(0) as u64) << x.exp();
+    Fixed32::new(scaled.isqrt() as i32, x.exp())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        fixed_tfhe::key_management::FheContext,
+        test_utils::test_assert_encrypted_eq,
+    };
+
+    #[test]
+    fn test_gradient_descent_step() {
+        let x = Fixed32::from(1., 16);
+        let step = x.gradient_descent_step(
+            Fixed32::from(2., 16),
+            Fixed32::from(0.25, 16),
+        );
+        assert_eq!(step, Fixed32::from(0.5, 16));
+    }
+
+    #[test]
+    fn test_gradient_descent_fits_constant() {
+        // Fits w to the constant 3 by minimizing (w - 3)^2
+        let target = Fixed32::from(3., 16);
+        let two = Fixed32::from(2., 16);
+        let lr = Fixed32::from(0.1, 16);
+        let mut w = Fixed32::new(0, 16);
+        for _ in 0..100 {
+            w = w.gradient_descent_step(two * (w - target), lr);
+        }
+        assert!(
+            w.approx_eq(target, Fixed32::from(1e-3, 16)),
+            "got {}",
+            w.to_f32()
+        );
+    }
+
+    #[test]
+    fn test_adam_fits_constant() {
+        let target = Fixed32::from(3., 16);
+        let two = Fixed32::from(2., 16);
+        let lr = Fixed32::from(0.05, 16);
+        let beta1 = Fixed32::from(0.9, 16);
+        let beta2 = Fixed32::from(0.999, 16);
+        let (mut m1, mut m2) = (Fixed32::new(0, 16), Fixed32::new(0, 16));
+        let mut w = Fixed32::new(0, 16);
+        for t in 1..=500 {
+            let gradient = two * (w - target);
+            w = w.adam_step(gradient, &mut m1, &mut m2, lr, beta1, beta2, t);
+        }
+        assert!(
+            w.approx_eq(target, Fixed32::from(0.05, 16)),
+            "got {}",
+            w.to_f32()
+        );
+    }
+
+    #[test]
+    fn test_adam_first_step_moves_by_lr() {
+        // With bias correction the first step has size `lr` whatever the
+        // gradient's magnitude
+        let lr = Fixed32::from(0.01, 16);
+        let beta1 = Fixed32::from(0.9, 16);
+        let beta2 = Fixed32::from(0.999, 16);
+        for gradient in [0.5, 40., -3.] {
+            let (mut m1, mut m2) = (Fixed32::new(0, 16), Fixed32::new(0, 16));
+            let w = Fixed32::from(1., 16).adam_step(
+                Fixed32::from(gradient, 16),
+                &mut m1,
+                &mut m2,
+                lr,
+                beta1,
+                beta2,
+                1,
+            );
+            let expected = 1. - 0.01 * f32::signum(gradient);
+            assert!(
+                w.approx_eq(
+                    Fixed32::from(expected, 16),
+                    Fixed32::from(1e-3, 16)
+                ),
+                "got {} for gradient {}",
+                w.to_f32(),
+                gradient
+            );
+        }
+    }
+
+    #[test]
+    fn test_adam_step_encrypted() {
+        let context = FheContext::new();
+        let client_key = context.client_key();
+        let lr = Fixed32::from(0.01, 16);
+        let beta1 = Fixed32::from(0.9, 16);
+        let beta2 = Fixed32::from(0.999, 16);
+        for gradient in [0.5, -3.] {
+            let mut m1 = TfheFixed32::from(client_key, 0., 16);
+            let mut m2 = TfheFixed32::from(client_key, 0., 16);
+            let param = adam_step_encrypted(
+                TfheFixed32::from(client_key, 1., 16),
+                TfheFixed32::from(client_key, gradient, 16),
+                &mut m1,
+                &mut m2,
+                lr,
+                beta1,
+                beta2,
+                1,
+            );
+
+            // The parameter moves against the gradient by about `lr`
+            let expected = 1. - 0.01 * f32::signum(gradient);
+            test_assert_encrypted_eq(client_key, &param, expected, 32);
+            test_assert_encrypted_eq(client_key, &m1, 0.1 * gradient, 8);
+            test_assert_encrypted_eq(
+                client_key,
+                &m2,
+                0.001 * gradient * gradient,
+                8,
+            );
+        }
+    }
+}
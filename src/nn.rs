@@ -0,0 +1,261 @@
+use crate::fixed_tfhe::TfheFixed32;
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+#[cfg(feature = "rayon")]
+use tfhe::set_server_key;
+use tfhe::{
+    ClientKey,
+    ServerKey,
+};
+
+pub struct FheLinearLayer {
+    // The encrypted weight matrix; each row produces one output
+    weights: Vec<Vec<TfheFixed32>>,
+
+    // The encrypted bias added to each output
+    biases: Vec<TfheFixed32>,
+
+    // The exponent shared by all weights, biases and inputs
+    exp: u32,
+}
+
+impl FheLinearLayer {
+    pub fn new(
+        client_key: &ClientKey,
+        weights: &[Vec<f32>],
+        biases: &[f32],
+        exp: u32,
+    ) -> Self {
+        // Encrypts plaintext weights and biases
+        if weights.len() != biases.len() {
+            panic!("The number of weight rows must match the number of biases!")
+        }
+
+        let weights = weights
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .map(|&w| TfheFixed32::from(client_key, w, exp))
+                    .collect()
+            })
+            .collect();
+        let biases = biases
+            .iter()
+            .map(|&b| TfheFixed32::from(client_key, b, exp))
+            .collect();
+
+        Self {
+            weights,
+            biases,
+            exp,
+        }
+    }
+
+    pub fn exp(&self) -> u32 {
+        self.exp
+    }
+
+    pub fn forward(&self, inputs: &[TfheFixed32]) -> Vec<TfheFixed32> {
+        // Computes `weights * inputs + biases` in the encrypted domain
+        self.weights
+            .iter()
+            .zip(&self.biases)
+            .map(|(row, bias)| {
+                if row.len() != inputs.len() {
+                    panic!(
+                        "The input dimension must match the number of weight \
+                    columns!"
+                    )
+                }
+
+                let mut sum = bias.clone();
+                for (weight, input) in row.iter().zip(inputs) {
+                    sum = sum + weight.clone() * input.clone();
+                }
+
+                sum
+            })
+            .collect()
+    }
+}
+
+pub struct FixedMat32Encrypted {
+    // The encrypted matrix entries, stored row by row
+    rows: Vec<Vec<TfheFixed32>>,
+
+    // The exponent shared by all entries
+    exp: u32,
+}
+
+impl FixedMat32Encrypted {
+    pub fn new(client_key: &ClientKey, rows: &[Vec<f32>], exp: u32) -> Self {
+        // Encrypts a plaintext matrix once so it can be applied to many inputs
+        let ncols = rows.first().map_or(0, |row| row.len());
+        if rows.iter().any(|row| row.len() != ncols) {
+            panic!("All matrix rows must have the same length!")
+        }
+
+        let rows = rows
+            .iter()
+            .map(|row| TfheFixed32::encrypt_slice(client_key, row, exp))
+            .collect();
+
+        Self { rows, exp }
+    }
+
+    pub fn rows(&self) -> &[Vec<TfheFixed32>] {
+        &self.rows
+    }
+
+    pub fn nrows(&self) -> usize {
+        self.rows.len()
+    }
+
+    pub fn ncols(&self) -> usize {
+        self.rows.first().map_or(0, |row| row.len())
+    }
+
+    pub fn exp(&self) -> u32 {
+        self.exp
+    }
+}
+
+#[cfg_attr(not(feature = "rayon"), allow(unused_variables))]
+pub fn batch_matmul(
+    weights: &FixedMat32Encrypted,
+    inputs: &[Vec<TfheFixed32>],
+    server_key: &ServerKey,
+) -> Vec<Vec<TfheFixed32>> {
+    // Computes `weights * x` for every input vector `x`. With the `rayon`
+    // feature the rows are evaluated in parallel, and `server_key`, which
+    // must be the key set on the calling thread, is installed on the worker
+    // threads
+    inputs
+        .iter()
+        .map(|input| {
+            if input.len() != weights.ncols() {
+                panic!(
+                    "The input dimension must match the number of weight \
+                columns!"
+                )
+            }
+
+            let dot = |row: &Vec<TfheFixed32>| {
+                row.iter()
+                    .zip(input)
+                    .map(|(weight, x)| weight.clone() * x.clone())
+                    .reduce(|a, b| a + b)
+                    .expect("The weight matrix must have at least one column!")
+            };
+
+            #[cfg(feature = "rayon")]
+            let outputs = weights
+                .rows
+                .par_iter()
+                .map_init(
+                    || set_server_key(server_key.clone()),
+                    |_, row| dot(row),
+                )
+                .collect();
+            #[cfg(not(feature = "rayon"))]
+            let outputs = weights.rows.iter().map(dot).collect();
+            outputs
+        })
+        .collect()
+}
+
+pub fn softmax_approx(logits: &[TfheFixed32]) -> Vec<TfheFixed32> {
+    // Computes e^x / sum(e^x) using `exp_approx`, so every logit must lie in
+    // [-4, 4]; the division multiplies by the encrypted reciprocal of the sum
+    let exps: Vec<TfheFixed32> = logits
+        .iter()
+        .map(|logit| logit.clone().exp_approx())
+        .collect();
+    let sum = exps
+        .iter()
+        .cloned()
+        .reduce(|a, b| a + b)
+        .expect("Softmax requires at least one logit!");
+    let inverse = sum.reciprocal();
+
+    exps.into_iter().map(|e| e * inverse.clone()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::test_assert_encrypted_eq;
+    use tfhe::{
+        generate_keys,
+        set_server_key,
+        ConfigBuilder,
+    };
+
+    #[test]
+    fn test_linear_layer_forward() {
+        let config = ConfigBuilder::default().build();
+        let (client_key, server_key) = generate_keys(config);
+
+        set_server_key(server_key);
+        let layer = FheLinearLayer::new(
+            &client_key,
+            &[vec![1., 2.], vec![0.5, -1.]],
+            &[0.5, 1.],
+            16,
+        );
+        let inputs = [
+            TfheFixed32::from(&client_key, 1.5, 16),
+            TfheFixed32::from(&client_key, 2., 16),
+        ];
+        let outputs = layer.forward(&inputs);
+
+        assert_eq!(outputs.len(), 2);
+        test_assert_encrypted_eq(&client_key, &outputs[0], 6., 0);
+        test_assert_encrypted_eq(&client_key, &outputs[1], -0.25, 0);
+    }
+
+    #[test]
+    fn test_softmax_approx() {
+        let config = ConfigBuilder::default().build();
+        let (client_key, server_key) = generate_keys(config);
+
+        set_server_key(server_key);
+        let logits = TfheFixed32::encrypt_slice(&client_key, &[1., 2., 3.], 24);
+        let outputs =
+            TfheFixed32::decrypt_slice(&client_key, &softmax_approx(&logits));
+
+        let total: f32 = outputs.iter().sum();
+        assert!((total - 1.).abs() < 0.05, "outputs sum to {}", total);
+        assert!(outputs[0] < outputs[1] && outputs[1] < outputs[2]);
+    }
+
+    #[test]
+    fn test_batch_matmul() {
+        let config = ConfigBuilder::default().build();
+        let (client_key, server_key) = generate_keys(config);
+
+        set_server_key(server_key.clone());
+        let weights = [vec![1., 0.5, -1., 2.], vec![0., -0.25, 1.5, 1.]];
+        let matrix = FixedMat32Encrypted::new(&client_key, &weights, 16);
+        let inputs =
+            [[1., 2., 3., 4.], [0.5, 0., -1., 0.25], [-2., 4., 0., 1.]];
+        let encrypted: Vec<Vec<TfheFixed32>> = inputs
+            .iter()
+            .map(|x| TfheFixed32::encrypt_slice(&client_key, x, 16))
+            .collect();
+
+        let outputs = batch_matmul(&matrix, &encrypted, &server_key);
+        assert_eq!((matrix.nrows(), matrix.ncols()), (2, 4));
+        assert_eq!(outputs.len(), 3);
+        for (output, input) in outputs.iter().zip(&inputs) {
+            let expected: Vec<f32> = weights
+                .iter()
+                .map(|row| row.iter().zip(input).map(|(w, x)| w * x).sum())
+                .collect();
+            assert_eq!(
+                TfheFixed32::decrypt_slice(&client_key, output),
+                expected
+            );
+        }
+    }
+}
@@ -0,0 +1,109 @@
+use crate::fixed::Fixed32;
+use pyo3::{
+    exceptions::{
+        PyValueError,
+        PyZeroDivisionError,
+    },
+    prelude::*,
+};
+
+#[pyclass(name = "Fixed32")]
+#[derive(Clone, Copy)]
+pub struct PyFixed32 {
+    inner: Fixed32,
+}
+
+#[pymethods]
+impl PyFixed32 {
+    #[new]
+    fn new(value: i32, exp: i32) -> Self {
+        Self {
+            inner: Fixed32::new(value, exp),
+        }
+    }
+
+    #[staticmethod]
+    fn from_float(value: f32, exp: i32) -> Self {
+        Self {
+            inner: Fixed32::from(value, exp),
+        }
+    }
+
+    #[getter]
+    fn value(&self) -> i32 {
+        self.inner.value()
+    }
+
+    #[getter]
+    fn exp(&self) -> i32 {
+        self.inner.exp()
+    }
+
+    fn to_float(&self) -> f32 {
+        self.inner.to_f32()
+    }
+
+    fn reciprocal(&self) -> Self {
+        Self {
+            inner: self.inner.reciprocal(),
+        }
+    }
+
+    fn __add__(&self, other: PyFixed32) -> Self {
+        Self {
+            inner: self.inner + other.inner,
+        }
+    }
+
+    fn __sub__(&self, other: PyFixed32) -> Self {
+        Self {
+            inner: self.inner - other.inner,
+        }
+    }
+
+    fn __mul__(&self, other: PyFixed32) -> PyResult<Self> {
+        // Raises instead of panicking across the FFI boundary
+        self.check_same_exp(other)?;
+        Ok(Self {
+            inner: self.inner * other.inner,
+        })
+    }
+
+    fn __truediv__(&self, other: PyFixed32) -> PyResult<Self> {
+        self.check_same_exp(other)?;
+        if other.inner.value() == 0 {
+            return Err(PyZeroDivisionError::new_err("division by zero"));
+        }
+
+        Ok(Self {
+            inner: self.inner / other.inner,
+        })
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "Fixed32(value={}, exp={}) ~ {}",
+            self.inner.value(),
+            self.inner.exp(),
+            self.inner.to_f32()
+        )
+    }
+}
+
+impl PyFixed32 {
+    fn check_same_exp(&self, other: PyFixed32) -> PyResult<()> {
+        if self.inner.exp() != other.inner.exp() {
+            return Err(PyValueError::new_err(
+                "both operands must have the same exponent",
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+#[pymodule]
+fn fixed_point_arithmetic(module: &Bound<'_, PyModule>) -> PyResult<()> {
+    module.add_class::<PyFixed32>()?;
+    Ok(())
+}
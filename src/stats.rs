@@ -0,0 +1,62 @@
+use crate::fixed::Fixed32;
+
+pub fn sum_of_squares(values: &[Fixed32]) -> Fixed32 {
+    // Accumulates the exact squares, each with `2 * exp` fractional bits, in
+    // an `i64`, and truncates once at the end. The accumulator can hold
+    // `n` squares of magnitude below 2^k while `n * 2^(2 * (k + exp)) < 2^63`:
+    // for values below 1 that is 2^31 terms at exp 16 but only 2^15 at
+    // exp 24. Panics if the accumulator or the result overflows
+    let exp = values.first().map_or(0, |x| x.exp());
+    let mut sum: i64 = 0;
+    for x in values {
+        if x.exp() != exp {
+            panic!(
+                "Only support fixed-point numbers with the same exponential!"
+            )
+        }
+
+        let square = x.value() as i64 * x.value() as i64;
+        sum = sum
+            .checked_add(square)
+            .expect("The sum of squares overflowed the 64-bit accumulator!");
+    }
+
+    let value = i32::try_from(sum >> exp)
+        .expect("The sum of squares does not fit in a fixed-point number!");
+    Fixed32::new(value, exp)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sum_of_squares() {
+        let values: Vec<Fixed32> = [1., 2., 3., 4., 5.]
+            .iter()
+            .map(|&x| Fixed32::from(x, 16))
+            .collect();
+        assert_eq!(sum_of_squares(&values), Fixed32::from(55., 16));
+        assert_eq!(sum_of_squares(&[]), Fixed32::new(0, 0));
+    }
+
+    #[test]
+    fn test_variance() {
+        // The population variance of [1, 2, 3, 4, 5] is 2
+        let mean = Fixed32::from(3., 24);
+        let deviations: Vec<Fixed32> = [1., 2., 3., 4., 5.]
+            .iter()
+            .map(|&x| Fixed32::from(x, 24) - mean)
+            .collect();
+        let total = sum_of_squares(&deviations);
+        let variance = Fixed32::new(total.value() / 5, 24);
+        assert_eq!(variance, Fixed32::from(2., 24));
+    }
+
+    #[test]
+    fn test_sum_of_squares_keeps_low_bits() {
+        // Each square is below one ULP at exp 16, but their sum is not
+        let values = vec![Fixed32::new(1 << 4, 16); 1 << 10];
+        assert_eq!(sum_of_squares(&values), Fixed32::new(4, 16));
+    }
+}
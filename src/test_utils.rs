@@ -0,0 +1,47 @@
+use crate::{
+    fixed::Fixed32,
+    fixed_tfhe::TfheFixed32,
+};
+use tfhe::ClientKey;
+
+#[track_caller]
+pub fn test_assert_encrypted_eq(
+    ck: &ClientKey,
+    result: &TfheFixed32,
+    expected: f32,
+    tol_ulps: u32,
+) {
+    // Decrypts `result` in one place and panics unless it is within
+    // `tol_ulps` units in the last place of `expected`, rounded to the
+    // result's exponent. The message shows both the real and the raw values
+    let expected_fixed = Fixed32::from(expected, result.exp() as i32);
+    test_assert_encrypted_raw_eq(ck, result, expected_fixed.value(), tol_ulps)
+}
+
+#[track_caller]
+pub fn test_assert_encrypted_raw_eq(
+    ck: &ClientKey,
+    result: &TfheFixed32,
+    expected_raw: i32,
+    tol_ulps: u32,
+) {
+    // Like `test_assert_encrypted_eq`, but takes the expected raw value, for
+    // bit patterns and integers that are easier to state that way
+    let exp = result.exp() as i32;
+    let actual = result.to_fixed32(ck);
+    let expected = Fixed32::new(expected_raw, exp);
+    let ulps = (actual.value() as i64 - expected_raw as i64).abs();
+    if ulps > tol_ulps as i64 {
+        panic!(
+            "encrypted result mismatch at exp {}: decrypted {} (raw {}), \
+            expected {} (raw {}), off by {} ULPs with a tolerance of {}",
+            exp,
+            actual.to_f32(),
+            actual.value(),
+            expected.to_f32(),
+            expected_raw,
+            ulps,
+            tol_ulps
+        )
+    }
+}
@@ -0,0 +1,176 @@
+use crate::fixed_tfhe::TfheFixed32;
+use std::ops::{
+    Add,
+    Div,
+    Mul,
+    Sub,
+};
+use tfhe::integer;
+
+#[derive(Clone)]
+pub struct TfheFixed32Traced {
+    // The wrapped encrypted value
+    inner: TfheFixed32,
+
+    // The number of sequential multiplications that produced this value
+    mul_depth: u32,
+}
+
+impl TfheFixed32Traced {
+    pub fn new(inner: TfheFixed32) -> Self {
+        // Freshly encrypted values have not been multiplied yet
+        Self {
+            inner,
+            mul_depth: 0,
+        }
+    }
+
+    pub fn inner(&self) -> &TfheFixed32 {
+        &self.inner
+    }
+
+    pub fn into_inner(self) -> TfheFixed32 {
+        self.inner
+    }
+
+    pub fn mul_depth(&self) -> u32 {
+        self.mul_depth
+    }
+
+    pub fn requires_bootstrapping(&self, max_depth: u32) -> bool {
+        self.mul_depth > max_depth
+    }
+
+    pub fn should_bootstrap(&self, depth_limit: u32) -> bool {
+        // Checked before a multiplication: true when the product would
+        // require bootstrapping, so `bootstrap` should be called first
+        depth_limit == 0 || self.requires_bootstrapping(depth_limit - 1)
+    }
+
+    pub fn bootstrap(self, server_key: &integer::ServerKey) -> Self {
+        // A bootstrapped value starts over with a fresh noise budget
+        Self::new(self.inner.bootstrap(server_key))
+    }
+}
+
+impl Add for TfheFixed32Traced {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self::Output {
+        // Additions do not increase the multiplicative depth
+        Self {
+            inner: self.inner + other.inner,
+            mul_depth: self.mul_depth.max(other.mul_depth),
+        }
+    }
+}
+
+impl Sub for TfheFixed32Traced {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self::Output {
+        Self {
+            inner: self.inner - other.inner,
+            mul_depth: self.mul_depth.max(other.mul_depth),
+        }
+    }
+}
+
+impl Mul for TfheFixed32Traced {
+    type Output = Self;
+
+    fn mul(self, other: Self) -> Self::Output {
+        Self {
+            inner: self.inner * other.inner,
+            mul_depth: self.mul_depth.max(other.mul_depth) + 1,
+        }
+    }
+}
+
+impl Div for TfheFixed32Traced {
+    type Output = Self;
+
+    fn div(self, other: Self) -> Self::Output {
+        // Division is counted like a multiplication
+        Self {
+            inner: self.inner / other.inner,
+            mul_depth: self.mul_depth.max(other.mul_depth) + 1,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::test_assert_encrypted_eq;
+    use tfhe::{
+        generate_keys,
+        set_server_key,
+        ConfigBuilder,
+    };
+
+    #[test]
+    fn test_polynomial_depth() {
+        let config = ConfigBuilder::default().build();
+        let (client_key, server_key) = generate_keys(config);
+
+        set_server_key(server_key);
+        let trace = |value: f32| {
+            TfheFixed32Traced::new(TfheFixed32::from(&client_key, value, 16))
+        };
+
+        // p(x) = 2x^3 + x + 3, evaluated as x * x * x to expose the depth
+        let x = trace(1.5);
+        let x_squared = x.clone() * x.clone();
+        assert_eq!(x_squared.mul_depth(), 1);
+
+        let x_cubed = x_squared * x.clone();
+        assert_eq!(x_cubed.mul_depth(), 2);
+
+        let result = trace(2.) * x_cubed + x + trace(3.);
+        assert_eq!(result.mul_depth(), 3);
+        test_assert_encrypted_eq(&client_key, result.inner(), 11.25, 0);
+
+        assert!(!result.requires_bootstrapping(3));
+        assert!(result.requires_bootstrapping(2));
+    }
+
+    #[test]
+    fn test_bootstrap_resets_depth() {
+        let config = ConfigBuilder::default().build();
+        let (client_key, server_key) = generate_keys(config);
+
+        set_server_key(server_key.clone());
+        let (integer_key, ..) = server_key.into_raw_parts();
+        let x = TfheFixed32Traced::new(TfheFixed32::from(&client_key, 1.5, 16));
+        let mut y = x.clone();
+        let mut bootstraps = 0;
+        for _ in 0..5 {
+            if y.should_bootstrap(2) {
+                y = y.bootstrap(&integer_key);
+                bootstraps += 1;
+            }
+            y = y * x.clone();
+            assert!(!y.requires_bootstrapping(2));
+        }
+
+        assert_eq!(bootstraps, 2);
+        assert_eq!(y.mul_depth(), 1);
+        test_assert_encrypted_eq(&client_key, y.inner(), 1.5f32.powi(6), 0);
+    }
+
+    #[test]
+    fn test_should_bootstrap() {
+        let config = ConfigBuilder::default().build();
+        let (client_key, server_key) = generate_keys(config);
+
+        set_server_key(server_key);
+        let x = TfheFixed32Traced::new(TfheFixed32::from(&client_key, 1.5, 16));
+        assert!(x.should_bootstrap(0));
+        assert!(!x.should_bootstrap(1));
+
+        let y = x.clone() * x;
+        assert!(y.should_bootstrap(1));
+        assert!(!y.should_bootstrap(2));
+    }
+}
@@ -0,0 +1,509 @@
+use crate::fixed::Fixed32;
+
+// Exponent of the CORDIC constants and of the intermediate values
+const CORDIC_EXP: i32 = 28;
+
+// π at `CORDIC_EXP`
+const CORDIC_PI: i64 = 843314857;
+
+// 1 / K, where K ≈ 1.6468 is the gain of the 20 CORDIC rotations
+const CORDIC_INV_GAIN: i64 = 163008219;
+
+// atan(2^-i) for i in 0..20
+const ATAN_TABLE: [Fixed32; 20] = [
+    Fixed32::new(210828714, CORDIC_EXP),
+    Fixed32::new(124459457, CORDIC_EXP),
+    Fixed32::new(65760959, CORDIC_EXP),
+    Fixed32::new(33381290, CORDIC_EXP),
+    Fixed32::new(16755422, CORDIC_EXP),
+    Fixed32::new(8385879, CORDIC_EXP),
+    Fixed32::new(4193963, CORDIC_EXP),
+    Fixed32::new(2097109, CORDIC_EXP),
+    Fixed32::new(1048571, CORDIC_EXP),
+    Fixed32::new(524287, CORDIC_EXP),
+    Fixed32::new(262144, CORDIC_EXP),
+    Fixed32::new(131072, CORDIC_EXP),
+    Fixed32::new(65536, CORDIC_EXP),
+    Fixed32::new(32768, CORDIC_EXP),
+    Fixed32::new(16384, CORDIC_EXP),
+    Fixed32::new(8192, CORDIC_EXP),
+    Fixed32::new(4096, CORDIC_EXP),
+    Fixed32::new(2048, CORDIC_EXP),
+    Fixed32::new(1024, CORDIC_EXP),
+    Fixed32::new(512, CORDIC_EXP),
+];
+
+// atanh(2^-i) for i in 1..=18
+const ATANH_TABLE: [Fixed32; 18] = [
+    Fixed32::new(147453245, CORDIC_EXP),
+    Fixed32::new(68561855, CORDIC_EXP),
+    Fixed32::new(33730852, CORDIC_EXP),
+    Fixed32::new(16799113, CORDIC_EXP),
+    Fixed32::new(8391340, CORDIC_EXP),
+    Fixed32::new(4194645, CORDIC_EXP),
+    Fixed32::new(2097195, CORDIC_EXP),
+    Fixed32::new(1048581, CORDIC_EXP),
+    Fixed32::new(524289, CORDIC_EXP),
+    Fixed32::new(262144, CORDIC_EXP),
+    Fixed32::new(131072, CORDIC_EXP),
+    Fixed32::new(65536, CORDIC_EXP),
+    Fixed32::new(32768, CORDIC_EXP),
+    Fixed32::new(16384, CORDIC_EXP),
+    Fixed32::new(8192, CORDIC_EXP),
+    Fixed32::new(4096, CORDIC_EXP),
+    Fixed32::new(2048, CORDIC_EXP),
+    Fixed32::new(1024, CORDIC_EXP),
+];
+
+// The shift of each of the 20 hyperbolic CORDIC iterations. Hyperbolic CORDIC
+// only converges if shifts 4 and 13 are repeated
+const HYPERBOLIC_SHIFTS: [usize; 20] = [
+    1, 2, 3, 4, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 13, 14, 15, 16, 17, 18,
+];
+
+// 1 / K, where K ≈ 0.8282 is the gain of the 20 hyperbolic rotations
+const CORDIC_HYPERBOLIC_INV_GAIN: i64 = 324135026;
+
+// ln 2 at `CORDIC_EXP`
+const CORDIC_LN_2: i64 = 186065279;
+
+// The first 128 fractional bits of 2 / π, most significant word first. For
+// 32-bit inputs this is the whole Payne-Hanek table: fewer than 2^-90 of
+// the product's integer part depends on the bits that are cut off
+const TWO_OVER_PI_BITS: [u64; 2] = [0xa2f9836e4e441529, 0xfc2757d1f534ddc0];
+
+// π / 2 with 62 fractional bits, rounded
+const HALF_PI_62: u64 = 0x6487ed5110b4611a;
+
+impl Fixed32 {
+    pub fn two_over_pi_reduction(self) -> (i32, Fixed32) {
+        // Payne-Hanek reduction: writes self = (4n + quadrant + f) * π / 2
+        // with quadrant in 0..4 and f in [0, 1), and returns
+        // `(quadrant, f * π / 2)`. The raw value is multiplied by 2 / π
+        // exactly in 160 bits, and the reduced angle is always rounded to
+        // exp 30, so it keeps 30 fractional bits however large or coarse the
+        // input is. sin(self) is then
+        // sin, cos, -sin or -cos of the reduced angle for quadrants 0 to 3
+        let magnitude = self.value().unsigned_abs() as u128;
+        let high = magnitude * TWO_OVER_PI_BITS[0] as u128;
+        let low = magnitude * TWO_OVER_PI_BITS[1] as u128;
+
+        // |self| * 2 / π = (high * 2^64 + low) * 2^-(128 + exp); keep two
+        // integer bits and 62 fractional bits of it
+        let window = ((high + (low >> 64)) >> (2 + self.exp())) as u64;
+        let (mut quadrant, mut fraction) =
+            ((window >> 62) as i32, window & ((1 << 62) - 1));
+        if self.value() < 0 {
+            // -(n + f) = -(n + 1) + (1 - f)
+            if fraction != 0 {
+                quadrant += 1;
+                fraction = (1 << 62) - fraction;
+            }
+            quadrant = -quadrant;
+        }
+
+        let angle = fraction as u128 * HALF_PI_62 as u128;
+        let angle = (angle + (1 << 93)) >> 94;
+
+        (quadrant.rem_euclid(4), Fixed32::new(angle as i32, 30))
+    }
+
+    pub fn cordic_exp(self) -> Fixed32 {
+        // Writes self = k * ln 2 + r with |r| <= ln 2 / 2, where rotating
+        // (1 / K, 0) by r gives (cosh r, sinh r), so e^r = cosh r + sinh r and
+        // e^self = e^r * 2^k. Panics if the result does not fit at the
+        // input's exponential
+        let z = to_cordic_exp(self);
+        let k = (z + CORDIC_LN_2 / 2).div_euclid(CORDIC_LN_2);
+        if k > 32 {
+            panic!("The result does not fit in a fixed-point number!")
+        }
+
+        let (cosh, sinh) = cordic_hyperbolic_rotation(z - k * CORDIC_LN_2);
+        let exp_r = cosh + sinh;
+        let scaled = if k >= 0 {
+            exp_r << k
+        } else {
+            exp_r >> (-k).min(63)
+        };
+        from_cordic_exp(scaled, self.exp())
+    }
+
+    pub fn cordic_ln(self) -> Fixed32 {
+        // Writes self = m * 2^k with m in [1, 2), so ln(self) = ln m + k ln 2,
+        // and ln m = 2 atanh((m - 1) / (m + 1)) comes from driving the vector
+        // (m + 1, m - 1) onto the x-axis
+        if self.value() <= 0 {
+            panic!("Only support the natural logarithm of positive fixed-point numbers!")
+        }
+
+        let leading_one_index = self.leading_significant_bit() as i32;
+        let m = to_cordic_exp(Fixed32::new(self.value(), leading_one_index));
+        let one = 1 << CORDIC_EXP;
+        let ln_m = 2 * cordic_hyperbolic_vectoring(m + one, m - one);
+        let k = (leading_one_index - self.exp()) as i64;
+        from_cordic_exp(ln_m + k * CORDIC_LN_2, self.exp())
+    }
+
+    pub fn cordic_atan(self) -> Fixed32 {
+        // Rotates the vector (1, self) onto the x-axis; the accumulated
+        // rotation is atan(self). Works for any input, not only [-1, 1]
+        let (_, angle) = cordic_vectoring(1 << CORDIC_EXP, to_cordic_exp(self));
+        from_cordic_exp(angle, self.exp())
+    }
+
+    pub fn cordic_hypot_angle(x: Fixed32, y: Fixed32) -> (Fixed32, Fixed32) {
+        // Computes `(sqrt(x^2 + y^2), atan2(y, x))` with a single CORDIC
+        // vectoring pass. Vectors with a negative `x` are first rotated by π
+        // so that the iteration converges; the angle lies in (-π, π]
+        if x.exp() != y.exp() {
+            panic!(
+                "Only support CORDIC between two fixed-point numbers with \
+            the same exponential!"
+            )
+        }
+        let exp = x.exp();
+        let (x, y) = (to_cordic_exp(x), to_cordic_exp(y));
+        if x == 0 && y == 0 {
+            return (Fixed32::new(0, exp), Fixed32::new(0, exp));
+        }
+
+        let (scaled_magnitude, angle) = if x < 0 {
+            let (magnitude, angle) = cordic_vectoring(-x, -y);
+            let half_turn = if y >= 0 { CORDIC_PI } else { -CORDIC_PI };
+            (magnitude, angle + half_turn)
+        } else {
+            cordic_vectoring(x, y)
+        };
+        let magnitude = (scaled_magnitude * CORDIC_INV_GAIN) >> CORDIC_EXP;
+
+        (from_cordic_exp(magnitude, exp), from_cordic_exp(angle, exp))
+    }
+}
+
+fn cordic_vectoring(x: i64, y: i64) -> (i64, i64) {
+    // Drives `y` to zero using only shifts and additions. Returns the scaled
+    // magnitude `K * sqrt(x^2 + y^2)`, with K ≈ 1.6468, and the angle
+    // `atan(y / x)`. Requires `x >= 0`; all values are at `CORDIC_EXP`
+    let (mut x, mut y, mut angle) = (x, y, 0i64);
+    for (i, atan) in ATAN_TABLE.iter().enumerate() {
+        let (dx, dy) = (y >> i, x >> i);
+        if y > 0 {
+            x += dx;
+            y -= dy;
+            angle += atan.value() as i64;
+        } else {
+            x -= dx;
+            y += dy;
+            angle -= atan.value() as i64;
+        }
+    }
+
+    (x, angle)
+}
+
+fn cordic_hyperbolic_rotation(angle: i64) -> (i64, i64) {
+    // Rotates (1 / K, 0) by the hyperbolic angle `angle`, which must be at
+    // most about 1.118 in magnitude, giving `(cosh(angle), sinh(angle))`.
+    // All values are at `CORDIC_EXP`
+    let (mut x, mut y, mut z) = (CORDIC_HYPERBOLIC_INV_GAIN, 0i64, angle);
+    for &i in &HYPERBOLIC_SHIFTS {
+        let (dx, dy) = (y >> i, x >> i);
+        let atanh = ATANH_TABLE[i - 1].value() as i64;
+        if z >= 0 {
+            x += dx;
+            y += dy;
+            z -= atanh;
+        } else {
+            x -= dx;
+            y -= dy;
+            z += atanh;
+        }
+    }
+
+    (x, y)
+}
+
+fn cordic_hyperbolic_vectoring(x: i64, y: i64) -> i64 {
+    // Drives `y` to zero and returns `atanh(y / x)`, which must be at most
+    // about 1.118 in magnitude. Requires `x > 0`; all values are at
+    // `CORDIC_EXP`
+    let (mut x, mut y, mut angle) = (x, y, 0i64);
+    for &i in &HYPERBOLIC_SHIFTS {
+        let (dx, dy) = (y >> i, x >> i);
+        let atanh = ATANH_TABLE[i - 1].value() as i64;
+        if y > 0 {
+            x -= dx;
+            y -= dy;
+            angle += atanh;
+        } else {
+            x += dx;
+            y += dy;
+            angle -= atanh;
+        }
+    }
+
+    angle
+}
+
+fn to_cordic_exp(x: Fixed32) -> i64 {
+    // 64 bits leave room for the integer part of any 32-bit input
+    let shift = CORDIC_EXP - x.exp();
+    if shift >= 0 {
+        (x.value() as i64) << shift
+    } else {
+        (x.value() as i64) >> -shift
+    }
+}
+
+fn from_cordic_exp(value: i64, exp: i32) -> Fixed32 {
+    // Rounds to the nearest value at `exp`
+    let shift = CORDIC_EXP - exp;
+    let value = if shift > 0 {
+        (value + (1 << (shift - 1))) >> shift
+    } else {
+        value << -shift
+    };
+
+    Fixed32::new(
+        i32::try_from(value)
+            .expect("The result does not fit in a fixed-point number!"),
+        exp,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::approx::eval_poly;
+    use std::f32::consts::{
+        FRAC_PI_2,
+        FRAC_PI_4,
+    };
+
+    fn taylor_exp(x: Fixed32) -> Fixed32 {
+        // Degree-8 Taylor polynomial of e^x around 0
+        let mut coefficients = [1f32; 9];
+        for i in 1..9 {
+            coefficients[i] = coefficients[i - 1] / i as f32;
+        }
+        let coefficients = coefficients.map(|c| Fixed32::from(c, x.exp()));
+        eval_poly(&coefficients, x)
+    }
+
+    fn taylor_ln(x: Fixed32) -> Fixed32 {
+        // Degree-8 Taylor polynomial of ln(1 + u) around u = 0
+        let coefficients: Vec<Fixed32> = (0..9)
+            .map(|i| match i {
+                0 => 0.,
+                _ => (if i % 2 == 1 { 1. } else { -1. }) / i as f32,
+            })
+            .map(|c| Fixed32::from(c, x.exp()))
+            .collect();
+        eval_poly(&coefficients, x - Fixed32::from(1., x.exp()))
+    }
+
+    #[test]
+    fn test_cordic_exp() {
+        for x in [-3., -1., -0.1, 0., 1e-4, 0.5, 1., 2.5, 4.5] {
+            let result = Fixed32::from(x, 24).cordic_exp();
+            let relative = (result.to_f32() - x.exp()).abs() / x.exp();
+            assert!(
+                relative < 1e-5,
+                "exp({}) = {}, got {}",
+                x,
+                x.exp(),
+                result.to_f32()
+            );
+        }
+
+        // Near the limits: e^21 is close to the largest integer at exp 0,
+        // and e^-20 underflows at exp 24
+        let result = Fixed32::new(21, 0).cordic_exp().value() as f64;
+        assert!((result - 21f64.exp()).abs() / 21f64.exp() < 1e-5);
+        assert_eq!(Fixed32::from(-20., 24).cordic_exp(), Fixed32::new(0, 24));
+    }
+
+    #[test]
+    fn test_cordic_exp_vs_taylor() {
+        // The Taylor polynomial is as good near 0, but CORDIC's range
+        // reduction keeps it accurate far from 0
+        let error = |f: fn(Fixed32) -> Fixed32, x: f32| {
+            (f(Fixed32::from(x, 24)).to_f32() - x.exp()).abs() / x.exp()
+        };
+        assert!(error(Fixed32::cordic_exp, 0.1) < 1e-5);
+        assert!(error(taylor_exp, 0.1) < 1e-5);
+        assert!(error(Fixed32::cordic_exp, 4.5) < 1e-5);
+        assert!(error(taylor_exp, 4.5) > 1e-2);
+        assert!(error(Fixed32::cordic_exp, -4.5) < 1e-4);
+        assert!(error(taylor_exp, -4.5) > 1.);
+    }
+
+    #[test]
+    fn test_cordic_ln() {
+        for x in [1e-4, 0.1, 0.5, 1., 1.5, 2., 10., 100.] {
+            // Compare against the input as rounded to 24 fractional bits
+            let x = Fixed32::from(x, 24);
+            let result = x.cordic_ln();
+            let x = x.to_f32();
+            assert!(
+                (result.to_f32() - x.ln()).abs() < 1e-5,
+                "ln({}) = {}, got {}",
+                x,
+                x.ln(),
+                result.to_f32()
+            );
+        }
+
+        // The smallest and largest positive values
+        let smallest = Fixed32::new(1, 24).cordic_ln().to_f32();
+        assert!((smallest - -24. * 2f32.ln()).abs() < 1e-5);
+        let largest = Fixed32::new(i32::MAX, 0).cordic_ln().to_f32();
+        assert_eq!(largest, (i32::MAX as f32).ln().round());
+    }
+
+    #[test]
+    fn test_cordic_ln_vs_taylor() {
+        let error = |f: fn(Fixed32) -> Fixed32, x: f32| {
+            (f(Fixed32::from(x, 24)).to_f32() - x.ln()).abs()
+        };
+        assert!(error(Fixed32::cordic_ln, 1.05) < 1e-5);
+        assert!(error(taylor_ln, 1.05) < 1e-5);
+        assert!(error(Fixed32::cordic_ln, 1.9) < 1e-5);
+        assert!(error(taylor_ln, 1.9) > 1e-2);
+        assert!(error(Fixed32::cordic_ln, 0.1) < 1e-5);
+        assert!(error(taylor_ln, 0.1) > 0.1);
+    }
+
+    #[test]
+    fn test_two_over_pi_reduction() {
+        let reduce = |x: f32| Fixed32::from(x, 24).two_over_pi_reduction();
+        let (quadrant, angle) = reduce(1.);
+        assert_eq!(quadrant, 0);
+        assert!(angle.approx_eq_ulps(Fixed32::from(1., 30), 1));
+
+        let (quadrant, angle) = reduce(2.);
+        assert_eq!((quadrant, angle.exp()), (1, 30));
+        assert!((angle.to_f32() - (2. - FRAC_PI_2)).abs() < 1e-7);
+
+        let (quadrant, angle) = reduce(-1.);
+        assert_eq!(quadrant, 3);
+        assert!((angle.to_f32() - (FRAC_PI_2 - 1.)).abs() < 1e-7);
+
+        assert_eq!(reduce(0.), (0, Fixed32::new(0, 30)));
+    }
+
+    #[test]
+    fn test_two_over_pi_reduction_large_arguments() {
+        // sin and cos of the reduced angle match f64 even at 2^20 radians,
+        // where reducing with a 32-bit π would lose all precision
+        for x in [
+            Fixed32::new(1 << 30, 10),
+            Fixed32::new(-(1 << 30) - 12345, 10),
+            Fixed32::new(987654321, 8),
+            Fixed32::new(i32::MAX, 0),
+            Fixed32::new(i32::MIN, 0),
+            Fixed32::new(355, 0),
+        ] {
+            let exact = x.value() as f64 / 2f64.powi(x.exp());
+            let (quadrant, angle) = x.two_over_pi_reduction();
+            let angle = angle.value() as f64 / 2f64.powi(angle.exp());
+            assert!((0. ..=std::f64::consts::FRAC_PI_2).contains(&angle));
+            let sin = match quadrant {
+                0 => angle.sin(),
+                1 => angle.cos(),
+                2 => -angle.sin(),
+                _ => -angle.cos(),
+            };
+            assert!(
+                (sin - exact.sin()).abs() < 1e-9,
+                "sin({}) = {}, got {}",
+                exact,
+                exact.sin(),
+                sin
+            );
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_cordic_ln_rejects_zero() {
+        Fixed32::new(0, 24).cordic_ln();
+    }
+
+    #[test]
+    fn test_cordic_atan() {
+        let zero = Fixed32::from(0., 24).cordic_atan().to_f32();
+        let one = Fixed32::from(1., 24).cordic_atan().to_f32();
+        let minus_one = Fixed32::from(-1., 24).cordic_atan().to_f32();
+        assert!(zero.abs() < 1e-5);
+        assert!((one - FRAC_PI_4).abs() < 1e-5);
+        assert!((minus_one + FRAC_PI_4).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_cordic_atan_intermediate_values() {
+        for x in [-0.9, -0.5, -0.1, 0.25, 0.5, 0.75, 0.99] {
+            let result = Fixed32::from(x, 24).cordic_atan();
+            assert_eq!(result.exp(), 24);
+            assert!(
+                (result.to_f32() - x.atan()).abs() < 1e-5,
+                "atan({}) = {}, got {}",
+                x,
+                x.atan(),
+                result.to_f32()
+            );
+        }
+
+        // Inputs outside [-1, 1] only need room for the integer part
+        let result = Fixed32::from(100., 16).cordic_atan();
+        assert!((result.to_f32() - 100f32.atan()).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_cordic_hypot_angle() {
+        // Compares against computing `hypot` and `atan2` separately
+        for (x, y) in [
+            (3., 4.),
+            (1., 0.),
+            (0., 2.),
+            (-1., 1.),
+            (-2., -0.5),
+            (0.3, -0.7),
+        ] {
+            let (magnitude, angle) = Fixed32::cordic_hypot_angle(
+                Fixed32::from(x, 20),
+                Fixed32::from(y, 20),
+            );
+            let (hypot, atan2) = (f32::hypot(x, y), f32::atan2(y, x));
+            assert!(
+                (magnitude.to_f32() - hypot).abs() < 1e-5 * hypot.max(1.),
+                "hypot({}, {}) = {}, got {}",
+                x,
+                y,
+                hypot,
+                magnitude.to_f32()
+            );
+            assert!(
+                (angle.to_f32() - atan2).abs() < 1e-5,
+                "atan2({}, {}) = {}, got {}",
+                y,
+                x,
+                atan2,
+                angle.to_f32()
+            );
+        }
+
+        let zero = Fixed32::new(0, 20);
+        assert_eq!(Fixed32::cordic_hypot_angle(zero, zero), (zero, zero));
+    }
+
+    #[test]
+    fn test_cordic_hypot_angle_matches_atan() {
+        let x = Fixed32::from(2., 24);
+        let y = Fixed32::from(1., 24);
+        let (_, angle) = Fixed32::cordic_hypot_angle(x, y);
+        let atan = Fixed32::from(0.5, 24).cordic_atan();
+        assert!((angle.to_f32() - atan.to_f32()).abs() < 1e-6);
+    }
+}
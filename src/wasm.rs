@@ -0,0 +1,77 @@
+use crate::fixed::Fixed32;
+use wasm_bindgen::prelude::*;
+
+// JavaScript bindings for `Fixed32`. Build with
+// `wasm-pack build --target nodejs -- --features wasm`. The Rust-side names
+// carry a `js_` prefix so they do not clash with the inherent methods
+#[wasm_bindgen]
+impl Fixed32 {
+    #[wasm_bindgen(constructor)]
+    pub fn js_new(value: i32, exp: i32) -> Fixed32 {
+        Fixed32::new(value, exp)
+    }
+
+    #[wasm_bindgen(js_name = from_f32)]
+    pub fn js_from_f32(value: f32, exp: i32) -> Fixed32 {
+        Fixed32::from(value, exp)
+    }
+
+    #[wasm_bindgen(getter, js_name = value)]
+    pub fn js_value(&self) -> i32 {
+        self.value()
+    }
+
+    #[wasm_bindgen(getter, js_name = exp)]
+    pub fn js_exp(&self) -> i32 {
+        self.exp()
+    }
+
+    #[wasm_bindgen(js_name = to_f32)]
+    pub fn js_to_f32(&self) -> f32 {
+        self.to_f32()
+    }
+
+    #[wasm_bindgen(js_name = reciprocal)]
+    pub fn js_reciprocal(&self) -> Fixed32 {
+        self.reciprocal()
+    }
+
+    #[wasm_bindgen(js_name = add)]
+    pub fn js_add(&self, other: &Fixed32) -> Fixed32 {
+        *self + *other
+    }
+
+    #[wasm_bindgen(js_name = sub)]
+    pub fn js_sub(&self, other: &Fixed32) -> Fixed32 {
+        *self - *other
+    }
+
+    #[wasm_bindgen(js_name = mul)]
+    pub fn js_mul(&self, other: &Fixed32) -> Result<Fixed32, JsError> {
+        // A panic would abort the wasm instance, so report mismatched
+        // exponents as a JavaScript error instead
+        if self.exp() != other.exp() {
+            return Err(JsError::new(
+                "Only support multiplication between two fixed-point numbers \
+                 with the same exponential!",
+            ));
+        }
+
+        Ok(*self * *other)
+    }
+
+    #[wasm_bindgen(js_name = div)]
+    pub fn js_div(&self, other: &Fixed32) -> Result<Fixed32, JsError> {
+        if self.exp() != other.exp() {
+            return Err(JsError::new(
+                "Only support division between two fixed-point numbers with \
+                 the same exponential!",
+            ));
+        }
+        if other.value() == 0 {
+            return Err(JsError::new("Division by zero error!"));
+        }
+
+        Ok(*self / *other)
+    }
+}
@@ -0,0 +1,27 @@
+#include "fixed_point_arithmetic.h"
+
+/* Returns 0 on success, or the index of the first failing check. */
+int fixed32_ffi_smoke_test(void) {
+  Fixed32Ffi a = fixed32_from_f32(2.5f, 24);
+  Fixed32Ffi b = fixed32_from_f32(4.0f, 24);
+
+  if (fixed32_to_f32(fixed32_add(a, b)) != 6.5f) {
+    return 1;
+  }
+
+  if (fixed32_to_f32(fixed32_mul(a, b)) != 10.0f) {
+    return 2;
+  }
+
+  float error = fixed32_to_f32(fixed32_reciprocal(b)) - 0.25f;
+  if (error < -1e-4f || error > 1e-4f) {
+    return 3;
+  }
+
+  Fixed32Ffi c = fixed32_new(3 << 8, 8);
+  if (c.value != 768 || c.exp != 8) {
+    return 4;
+  }
+
+  return 0;
+}